@@ -0,0 +1,19 @@
+fn main() {
+	println!("cargo:rerun-if-changed=proto/wm.proto");
+	println!("cargo:rerun-if-changed=proto/service.proto");
+	println!("cargo:rerun-if-changed=proto/system.proto");
+	println!("cargo:rerun-if-changed=proto/message.proto");
+
+	prost_build::Config::new()
+		.type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
+		.compile_protos(
+			&[
+				"proto/wm.proto",
+				"proto/service.proto",
+				"proto/system.proto",
+				"proto/message.proto",
+			],
+			&["proto"],
+		)
+		.unwrap();
+}
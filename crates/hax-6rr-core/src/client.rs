@@ -0,0 +1,212 @@
+use crate::endpoint::ServerEndpoint;
+use crate::error::WmResponse;
+use crate::wm;
+use anyhow::{Context, Result};
+use std::time::Duration;
+use url::Url;
+
+/// Headers sent with every request so the server sees a real terminal.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HeaderProfile {
+	pub user_agent: String,
+	pub revision: u32,
+}
+
+impl Default for HeaderProfile {
+	fn default() -> Self {
+		Self {
+			user_agent: String::from("V388 Client; 285013501138"),
+			revision: wm::SystemConstant::ProtobufMessageRevision as u32,
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct ClientConfig {
+	pub headers: HeaderProfile,
+	pub timeout: Duration,
+	pub accept_invalid_certs: bool,
+}
+
+impl Default for ClientConfig {
+	fn default() -> Self {
+		Self {
+			headers: HeaderProfile::default(),
+			timeout: Duration::from_secs(15),
+			accept_invalid_certs: true,
+		}
+	}
+}
+
+/// One pooled connection to a WMMT6 server, cheap to clone.
+#[derive(Clone)]
+pub struct WmClient {
+	client: reqwest::Client,
+	base: Url,
+	headers: HeaderProfile,
+}
+
+impl WmClient {
+	pub fn new(endpoint: &ServerEndpoint) -> Result<Self> {
+		Self::with_config(endpoint, ClientConfig::default())
+	}
+
+	pub fn with_config(endpoint: &ServerEndpoint, config: ClientConfig) -> Result<Self> {
+		let client = reqwest::Client::builder()
+			.danger_accept_invalid_certs(config.accept_invalid_certs)
+			.timeout(config.timeout)
+			.build()?;
+
+		Ok(Self {
+			client,
+			base: endpoint.base().clone(),
+			headers: config.headers,
+		})
+	}
+
+	pub fn base(&self) -> &Url {
+		&self.base
+	}
+
+	fn builder(&self, method: reqwest::Method, path: &str) -> Result<reqwest::RequestBuilder> {
+		let content_type = format!("application/x-protobuf; revision={}", self.headers.revision);
+
+		Ok(self
+			.client
+			.request(method, self.base.join(path)?)
+			.header("user-agent", &self.headers.user_agent)
+			.header("cache-control", "no-cache")
+			.header("accept", &content_type)
+			.header("content-type", &content_type))
+	}
+
+	async fn send<O: prost::Message + Default>(req: reqwest::RequestBuilder) -> Result<O> {
+		let res = req.send().await.context("Request failed")?;
+		let path = res.url().path().to_string();
+		let status = res.status();
+
+		if status != 200 {
+			anyhow::bail!("Request to {path} failed with {status}");
+		}
+		let bytes = res
+			.bytes()
+			.await
+			.with_context(|| format!("Reading the reply to {path} ({status})"))?;
+		O::decode(bytes).with_context(|| format!("Decoding the reply to {path} ({status})"))
+	}
+
+	/// Sends `req` and turns a non-success `ErrorCode` in the reply into a `WmError`.
+	pub async fn request<I: prost::Message, O: prost::Message + Default + WmResponse>(
+		&self,
+		path: &str,
+		req: I,
+	) -> Result<O> {
+		let res: O = Self::send(
+			self.builder(reqwest::Method::POST, path)?
+				.body(req.encode_to_vec()),
+		)
+		.await?;
+		Ok(res.check()?)
+	}
+
+	pub async fn resource<O: prost::Message + Default>(&self, path: &str) -> Result<O> {
+		Self::send(self.builder(reqwest::Method::GET, path)?).await
+	}
+
+	pub async fn load_user(&self, req: wm::LoadUserRequest) -> Result<wm::LoadUserResponse> {
+		self.request("method/load_user", req).await
+	}
+
+	pub async fn create_user(&self, req: wm::CreateUserRequest) -> Result<wm::CreateUserResponse> {
+		self.request("method/create_user", req).await
+	}
+
+	pub async fn create_car(&self, req: wm::CreateCarRequest) -> Result<wm::CreateCarResponse> {
+		self.request("method/create_car", req).await
+	}
+
+	pub async fn ask_access_code(
+		&self,
+		req: wm::AskAccessCodeRequest,
+	) -> Result<wm::AskAccessCodeResponse> {
+		self.request("method/ask_access_code", req).await
+	}
+
+	pub async fn load_car(&self, req: wm::LoadCarRequest) -> Result<wm::LoadCarResponse> {
+		self.request("method/load_car", req).await
+	}
+
+	pub async fn load_game_history(
+		&self,
+		req: wm::LoadGameHistoryRequest,
+	) -> Result<wm::LoadGameHistoryResponse> {
+		self.request("method/load_game_history", req).await
+	}
+
+	pub async fn load_time_attack_record(
+		&self,
+		req: wm::LoadTimeAttackRecordRequest,
+	) -> Result<wm::LoadTimeAttackRecordResponse> {
+		self.request("method/load_time_attack_record", req).await
+	}
+
+	pub async fn update_car(&self, req: wm::UpdateCarRequest) -> Result<wm::UpdateCarResponse> {
+		self.request("method/update_car", req).await
+	}
+
+	pub async fn save_game_result(
+		&self,
+		req: wm::SaveGameResultRequest,
+	) -> Result<wm::SaveGameResultResponse> {
+		self.request("method/save_game_result", req).await
+	}
+
+	pub async fn load_unreceived_user_items(
+		&self,
+		req: wm::LoadUnreceivedUserItemsRequest,
+	) -> Result<wm::LoadUnreceivedUserItemsResponse> {
+		self.request("method/load_unreceived_user_items", req).await
+	}
+
+	pub async fn check_item_receivable_cars(
+		&self,
+		req: wm::CheckItemReceivableCarsRequest,
+	) -> Result<wm::CheckItemReceivableCarsResponse> {
+		self.request("method/check_item_receivable_cars", req).await
+	}
+
+	pub async fn receive_user_items(
+		&self,
+		req: wm::ReceiveUserItemsRequest,
+	) -> Result<wm::ReceiveUserItemsResponse> {
+		self.request("method/receive_user_items", req).await
+	}
+
+	pub async fn load_scratch_information(
+		&self,
+		req: wm::LoadScratchInformationRequest,
+	) -> Result<wm::LoadScratchInformationResponse> {
+		self.request("method/load_scratch_information", req).await
+	}
+
+	pub async fn save_scratch_sheet(
+		&self,
+		req: wm::SaveScratchSheetRequest,
+	) -> Result<wm::SaveScratchSheetResponse> {
+		self.request("method/save_scratch_sheet", req).await
+	}
+
+	pub async fn turn_scratch_sheet(
+		&self,
+		req: wm::TurnScratchSheetRequest,
+	) -> Result<wm::TurnScratchSheetResponse> {
+		self.request("method/turn_scratch_sheet", req).await
+	}
+
+	pub async fn save_terminal_result(
+		&self,
+		req: wm::SaveTerminalResultRequest,
+	) -> Result<wm::SaveTerminalResultResponse> {
+		self.request("method/save_terminal_result", req).await
+	}
+}
@@ -0,0 +1,92 @@
+//! Field-by-field comparison of protobuf messages for previews before anything is sent.
+use anyhow::Result;
+use serde::Serialize;
+
+/// One field that differs between two values, with both sides already formatted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+	/// Dotted path such as `car.wheel`.
+	pub field: String,
+	pub old: String,
+	pub new: String,
+}
+
+/// Lists every field of `new` whose value isn't the same in `old`.
+pub fn diff<T: Serialize>(old: &T, new: &T) -> Result<Vec<Change>> {
+	let mut changes = Vec::new();
+	diff_values(
+		"",
+		&serde_json::to_value(old)?,
+		&serde_json::to_value(new)?,
+		&mut changes,
+	);
+	Ok(changes)
+}
+
+fn diff_values(
+	field: &str,
+	old: &serde_json::Value,
+	new: &serde_json::Value,
+	changes: &mut Vec<Change>,
+) {
+	use serde_json::Value;
+
+	if old == new {
+		return;
+	}
+	match (old, new) {
+		(Value::Object(old), Value::Object(new)) => {
+			for (key, new_value) in new.iter() {
+				let old_value = old.get(key).unwrap_or(&Value::Null);
+				let field = if field.is_empty() {
+					key.clone()
+				} else {
+					format!("{field}.{key}")
+				};
+				diff_values(&field, old_value, new_value, changes);
+			}
+		}
+		// Item lists are long, the count says enough in a preview
+		(Value::Array(old), Value::Array(new)) => changes.push(Change {
+			field: String::from(field),
+			old: format!("{} entries", old.len()),
+			new: format!("{} entries", new.len()),
+		}),
+		_ => changes.push(Change {
+			field: String::from(field),
+			old: old.to_string(),
+			new: new.to_string(),
+		}),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::wm;
+
+	#[test]
+	fn nested_fields_are_dotted() {
+		let old = wm::LoadCarResponse::default();
+		let mut new = old.clone();
+		new.car.wheel = 3;
+		new.setting.bgm = 2;
+
+		let changes = diff(&old, &new).unwrap();
+		assert_eq!(
+			changes,
+			vec![
+				Change {
+					field: String::from("car.wheel"),
+					old: String::from("0"),
+					new: String::from("3"),
+				},
+				Change {
+					field: String::from("setting.bgm"),
+					old: String::from("0"),
+					new: String::from("2"),
+				},
+			]
+		);
+	}
+}
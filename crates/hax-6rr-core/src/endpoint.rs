@@ -0,0 +1,151 @@
+use anyhow::{bail, format_err, Result};
+use url::Url;
+
+/// Port WMMT6 servers listen on unless the URL names another one.
+pub const DEFAULT_PORT: u16 = 9002;
+/// Path requests go under when the URL doesn't have one of its own.
+pub const DEFAULT_PATH_PREFIX: &str = "wmmt6";
+
+/// A validated server address, already pointing at the directory requests are made under.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerEndpoint {
+	base: Url,
+}
+
+impl ServerEndpoint {
+	/// Accepts `host`, `host:port` and full `http(s)://host[:port][/prefix]` URLs.
+	///
+	/// A missing scheme means https, a missing port means `DEFAULT_PORT` and a missing
+	/// path means `DEFAULT_PATH_PREFIX`.
+	pub fn parse(input: &str) -> Result<Self> {
+		let input = input.trim();
+		if input.is_empty() {
+			bail!("Enter a server URL");
+		}
+		let input = if input.contains("://") {
+			String::from(input)
+		} else {
+			format!("https://{input}")
+		};
+
+		let mut base =
+			Url::parse(&input).map_err(|err| format_err!("Invalid server URL: {err}"))?;
+		if !matches!(base.scheme(), "http" | "https") {
+			bail!("Unsupported scheme {}, use http or https", base.scheme());
+		}
+		if base.host_str().unwrap_or_default().is_empty() {
+			bail!("Server URL has no host");
+		}
+		if base.query().is_some() || base.fragment().is_some() {
+			bail!("Server URL can't have a query or fragment");
+		}
+		// `Url` forgets ports that are the scheme's default, so look at what was typed
+		if !has_explicit_port(&input) {
+			_ = base.set_port(Some(DEFAULT_PORT));
+		}
+
+		let prefix = match base.path().trim_matches('/') {
+			"" => DEFAULT_PATH_PREFIX,
+			prefix => prefix,
+		};
+		let path = format!("/{prefix}/");
+		base.set_path(&path);
+
+		Ok(Self { base })
+	}
+
+	/// The URL request paths such as `method/load_user` are joined onto.
+	pub fn base(&self) -> &Url {
+		&self.base
+	}
+
+	pub fn port(&self) -> u16 {
+		self.base.port_or_known_default().unwrap_or(DEFAULT_PORT)
+	}
+
+	pub fn set_port(&mut self, port: u16) {
+		// Only fails for URLs that can't have a host, which `parse` already rejects
+		_ = self.base.set_port(Some(port));
+	}
+}
+
+impl std::fmt::Display for ServerEndpoint {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.base)
+	}
+}
+
+fn has_explicit_port(url: &str) -> bool {
+	let authority = url.split_once("://").map_or(url, |(_, rest)| rest);
+	let authority = authority.split(['/', '?', '#']).next().unwrap_or_default();
+	let host = authority
+		.rsplit_once('@')
+		.map_or(authority, |(_, host)| host);
+	// IPv6 literals are bracketed and full of colons themselves
+	let host = host.rsplit_once(']').map_or(host, |(_, rest)| rest);
+
+	host.contains(':')
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn base(input: &str) -> String {
+		ServerEndpoint::parse(input).unwrap().base().to_string()
+	}
+
+	#[test]
+	fn accepted_forms() {
+		assert_eq!(base("example.com"), "https://example.com:9002/wmmt6/");
+		assert_eq!(base("  example.com  "), "https://example.com:9002/wmmt6/");
+		assert_eq!(base("example.com:8080"), "https://example.com:8080/wmmt6/");
+		assert_eq!(base("http://example.com"), "http://example.com:9002/wmmt6/");
+		assert_eq!(base("http://example.com:80"), "http://example.com/wmmt6/");
+		assert_eq!(
+			base("https://example.com:443/"),
+			"https://example.com/wmmt6/"
+		);
+		assert_eq!(
+			base("http://127.0.0.1:9002/"),
+			"http://127.0.0.1:9002/wmmt6/"
+		);
+		assert_eq!(base("[::1]"), "https://[::1]:9002/wmmt6/");
+		assert_eq!(base("http://[::1]:1234"), "http://[::1]:1234/wmmt6/");
+		assert_eq!(
+			base("https://example.com/bayshore"),
+			"https://example.com:9002/bayshore/"
+		);
+		assert_eq!(
+			base("https://example.com:9000/api/wmmt6/"),
+			"https://example.com:9000/api/wmmt6/"
+		);
+	}
+
+	#[test]
+	fn rejected_forms() {
+		for input in [
+			"",
+			"   ",
+			"ftp://example.com",
+			"https://",
+			"https://example.com:99999",
+			"https://example.com/?a=b",
+			"https://example.com/#top",
+		] {
+			assert!(
+				ServerEndpoint::parse(input).is_err(),
+				"{input:?} was accepted"
+			);
+		}
+	}
+
+	#[test]
+	fn port_can_be_overridden() {
+		let mut endpoint = ServerEndpoint::parse("example.com").unwrap();
+		assert_eq!(endpoint.port(), DEFAULT_PORT);
+
+		endpoint.set_port(10082);
+		assert_eq!(endpoint.base().as_str(), "https://example.com:10082/wmmt6/");
+	}
+}
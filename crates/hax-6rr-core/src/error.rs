@@ -0,0 +1,175 @@
+use crate::wm;
+
+/// A non-success `ErrorCode` returned by the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WmError {
+	Request,
+	NotFound,
+	IdServer,
+	IdBanned,
+	UserLocked,
+	UserVersion,
+	NameConflicted,
+	Forbidden,
+	UserSucceeded,
+	BeingTransferred,
+	ScratchLocked,
+	BingoRevengeLocked,
+	BingoAlreadyLinked,
+	BingoFinished,
+	BingoReachedMaxUser,
+	CarNotFound,
+	BingoCardNotFound,
+	Unknown(i32),
+}
+
+impl WmError {
+	/// Returns `None` for `ERR_SUCCESS`.
+	pub fn from_code(code: i32) -> Option<Self> {
+		let code = match wm::ErrorCode::try_from(code) {
+			Ok(code) => code,
+			Err(_) => return Some(WmError::Unknown(code)),
+		};
+
+		match code {
+			wm::ErrorCode::ErrSuccess => None,
+			wm::ErrorCode::ErrRequest => Some(WmError::Request),
+			wm::ErrorCode::ErrNotFound => Some(WmError::NotFound),
+			wm::ErrorCode::ErrIdServer => Some(WmError::IdServer),
+			wm::ErrorCode::ErrIdBanned => Some(WmError::IdBanned),
+			wm::ErrorCode::ErrUserLocked => Some(WmError::UserLocked),
+			wm::ErrorCode::ErrUserVersion => Some(WmError::UserVersion),
+			wm::ErrorCode::ErrNameConflicted => Some(WmError::NameConflicted),
+			wm::ErrorCode::ErrForbidden => Some(WmError::Forbidden),
+			wm::ErrorCode::ErrUserSucceeded => Some(WmError::UserSucceeded),
+			wm::ErrorCode::ErrBeingTransferred => Some(WmError::BeingTransferred),
+			wm::ErrorCode::ErrScratchLocked => Some(WmError::ScratchLocked),
+			wm::ErrorCode::ErrBingoRevengeLocked => Some(WmError::BingoRevengeLocked),
+			wm::ErrorCode::ErrBingoAlredyLinked => Some(WmError::BingoAlreadyLinked),
+			wm::ErrorCode::ErrBingoFinished => Some(WmError::BingoFinished),
+			wm::ErrorCode::ErrBingoReachedMaxUser => Some(WmError::BingoReachedMaxUser),
+			wm::ErrorCode::ErrCarNotFound => Some(WmError::CarNotFound),
+			wm::ErrorCode::ErrBingoCardNotFound => Some(WmError::BingoCardNotFound),
+		}
+	}
+}
+
+impl std::fmt::Display for WmError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			WmError::Request => f.write_str("Malformed request (ERR_REQUEST)"),
+			WmError::NotFound => f.write_str("Not found (ERR_NOT_FOUND)"),
+			WmError::IdServer => f.write_str("ID server error (ERR_ID_SERVER)"),
+			WmError::IdBanned => f.write_str("Card is banned (ERR_ID_BANNED)"),
+			WmError::UserLocked => f.write_str("User is locked (ERR_USER_LOCKED)"),
+			WmError::UserVersion => f.write_str("User version mismatch (ERR_USER_VERSION)"),
+			WmError::NameConflicted => f.write_str("Name already taken (ERR_NAME_CONFLICTED)"),
+			WmError::Forbidden => f.write_str("Forbidden (ERR_FORBIDDEN)"),
+			WmError::UserSucceeded => f.write_str("User was succeeded (ERR_USER_SUCCEEDED)"),
+			WmError::BeingTransferred => {
+				f.write_str("User is being transferred (ERR_BEING_TRANSFERRED)")
+			}
+			WmError::ScratchLocked => f.write_str("Scratch is locked (ERR_SCRATCH_LOCKED)"),
+			WmError::BingoRevengeLocked => {
+				f.write_str("Bingo revenge is locked (ERR_BINGO_REVENGE_LOCKED)")
+			}
+			WmError::BingoAlreadyLinked => {
+				f.write_str("Bingo card already linked (ERR_BINGO_ALREDY_LINKED)")
+			}
+			WmError::BingoFinished => f.write_str("Bingo is finished (ERR_BINGO_FINISHED)"),
+			WmError::BingoReachedMaxUser => {
+				f.write_str("Bingo reached max users (ERR_BINGO_REACHED_MAX_USER)")
+			}
+			WmError::CarNotFound => f.write_str("Car not found (ERR_CAR_NOT_FOUND)"),
+			WmError::BingoCardNotFound => {
+				f.write_str("Bingo card not found (ERR_BINGO_CARD_NOT_FOUND)")
+			}
+			WmError::Unknown(code) => write!(f, "Unknown error code {code}"),
+		}
+	}
+}
+
+impl std::error::Error for WmError {}
+
+/// Response messages that carry a required `error: ErrorCode`.
+pub trait WmResponse: Sized {
+	fn error_code(&self) -> i32;
+
+	fn check(self) -> Result<Self, WmError> {
+		match WmError::from_code(self.error_code()) {
+			Some(err) => Err(err),
+			None => Ok(self),
+		}
+	}
+}
+
+macro_rules! impl_wm_response {
+	($($response:ident,)*) => {
+		$(
+			impl WmResponse for wm::$response {
+				fn error_code(&self) -> i32 {
+					self.error
+				}
+			}
+		)*
+	};
+}
+
+impl_wm_response!(
+	RegisterSystemInfoResponse,
+	RegisterSystemStatsResponse,
+	RegisterGhostTrailResponse,
+	LoadUserResponse,
+	UpdateUserSessionResponse,
+	LoadDriveInformationResponse,
+	LoadCarResponse,
+	CreateCarResponse,
+	LoadGameHistoryResponse,
+	UpdateCarResponse,
+	SaveGameResultResponse,
+	SaveChargeResponse,
+	LoadGhostBattleInfoResponse,
+	LoadStampTargetResponse,
+	LockStampTargetResponse,
+	LoadGhostCompetitionInfoResponse,
+	LoadGhostKoshienInfoResponse,
+	LoadTimeAttackRecordResponse,
+	SaveTerminalResultResponse,
+	PingResponse,
+	LoadTerminalInformationResponse,
+	CreateUserResponse,
+	AskAccessCodeResponse,
+	RegisterOpponentGhostResponse,
+	GrantCarRightResponse,
+	LoadGhostCompetitionRankingResponse,
+	LoadGhostKoshienRankingResponse,
+	LoadUnreceivedUserItemsResponse,
+	LoadBookmarksResponse,
+	SaveBookmarksResponse,
+	StartTransferResponse,
+	SaveScreenshotResponse,
+	SubmitClientLogResponse,
+	LoadScratchInformationResponse,
+	SaveScratchSheetResponse,
+	TurnScratchSheetResponse,
+	CheckItemReceivableCarsResponse,
+	ReceiveUserItemsResponse,
+	UpdateEventModeSerialResponse,
+	ParticipateInInviteFriendCampaignResponse,
+	ConsumeUserItemResponse,
+	SearchCarsByLevelResponse,
+	SearchCarsResponse,
+	LoadPathsAndTuningsResponse,
+	LoadGhostDriveDataResponse,
+	LoadGhostExpeditionInfoResponse,
+	LoadGhostExpeditionTargetByPathResponse,
+	LoadGhostExpeditionTargetsResponse,
+	LoadGhostExpeditionResultResponse,
+	SaveNetworkStatResponse,
+	LoadGhostBingoTargetsResponse,
+	LoadBingoStatsResponse,
+	BingoReceivableItemsResponse,
+	MergeBingoCardResponse,
+	LockCrownResponse,
+	LockWantedResponse,
+);
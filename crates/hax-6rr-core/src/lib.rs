@@ -0,0 +1,9 @@
+//! Protocol types, item catalogs and a typed client for WMMT6 servers, free of any GUI dependencies.
+pub mod client;
+pub mod diff;
+pub mod endpoint;
+pub mod error;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod snapshot;
+pub mod wm;
@@ -0,0 +1,859 @@
+//! In-process stand-in for a WMMT6 server so requests can be tested without a live one.
+//!
+//! The protocol only gives field names, so where the mock has to decide what a server does
+//! with them it goes by the guesses below. None of them are checked against a real server,
+//! tests leaning on them only show the app agrees with the mock.
+//!
+//! - `LoadUserResponse.unused_car_tickets`, `LoadUnreceivedUserItemsResponse.owned_user_items`
+//!   and `LoadScratchInformationResponse.owned_user_items` are three separate lists, see
+//!   [`MockUser`].
+//! - `SaveGameResultRequest.earned_user_items` that are car tickets become unused tickets,
+//!   anything else waits to be received.
+//! - A scratched square's item is listed with the scratch information and also waits to be
+//!   received.
+//! - `SaveGameResultRequest.preserved_titles` replaces the car's `earned_titles` when it isn't
+//!   empty.
+//! - A car can receive an item it doesn't own yet, tickets and consumables can't be received.
+//! - Time attack results only replace a slower personal best.
+use crate::*;
+use prost::Message;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use url::Url;
+
+pub const ACCESS_CODE: &str = "12345678901234567890";
+pub const CHIP_ID: &str = "0123456789ABCDEF0123456789ABCDEF";
+pub const USER_ID: u32 = 1;
+pub const CAR_ID: u32 = 100;
+
+#[derive(Clone, Default)]
+pub struct MockUser {
+	pub user_id: u32,
+	pub access_code: String,
+	pub chip_id: String,
+	/// Sent as `unused_car_tickets`, spent on new cars.
+	pub car_tickets: Vec<wm::UserItem>,
+	/// Sent by `load_unreceived_user_items`, until a car receives them.
+	pub unreceived_items: Vec<wm::UserItem>,
+	/// Sent with the scratch information, everything scratched so far.
+	pub scratch_items: Vec<wm::UserItem>,
+	pub scratch_sheets: Vec<wm::ScratchSheet>,
+	pub current_sheet: u32,
+	/// Makes scratching and turning sheets fail with `ERR_SCRATCH_LOCKED`.
+	pub scratch_locked: bool,
+}
+
+/// A car's personal best on one course.
+#[derive(Clone, Default)]
+pub struct MockTaRecord {
+	pub car_id: u32,
+	pub result: wm::save_game_result_request::TimeAttackResult,
+}
+
+#[derive(Default)]
+pub struct MockState {
+	pub users: Vec<MockUser>,
+	/// Cars are kept as the reply `load_car` would give for them.
+	pub cars: Vec<wm::LoadCarResponse>,
+	/// Cars flagged with `to_be_deleted`, still listed until the server gets around to them.
+	pub deleted_car_ids: Vec<u32>,
+	/// What `load_game_history` replies for a car id, cars missing here have no history yet.
+	pub game_histories: HashMap<u32, wm::LoadGameHistoryResponse>,
+	/// Rankings are worked out from these, fastest first.
+	pub ta_records: Vec<MockTaRecord>,
+	pub next_user_item_id: u32,
+}
+
+impl MockState {
+	/// One user owning a single stock car.
+	pub fn seeded() -> Self {
+		let car = wm::Car {
+			car_id: Some(CAR_ID),
+			user_id: Some(USER_ID),
+			region_id: Some(13),
+			name: Some(String::from("ＴＥＳＴ")),
+			manufacturer: Some(0),
+			model: Some(wm::Cars::FD3S as u32),
+			visual_model: Some(wm::Cars::FD3S as u32),
+			default_color: Some(0),
+			title: String::from("Mock"),
+			level: 1,
+			country: Some(String::from("JPN")),
+			..Default::default()
+		};
+
+		Self {
+			users: vec![MockUser {
+				user_id: USER_ID,
+				access_code: String::from(ACCESS_CODE),
+				chip_id: String::from(CHIP_ID),
+				..Default::default()
+			}],
+			cars: vec![wm::LoadCarResponse {
+				car,
+				setting: wm::CarSetting {
+					car_id: Some(CAR_ID),
+					volume: 2,
+					..Default::default()
+				},
+				odometer: 1000,
+				play_count: 10,
+				..Default::default()
+			}],
+			deleted_car_ids: vec![],
+			game_histories: HashMap::new(),
+			ta_records: vec![],
+			next_user_item_id: 1,
+		}
+	}
+
+	pub fn car(&self, car_id: u32) -> Option<&wm::LoadCarResponse> {
+		self.cars.iter().find(|car| car.car.car_id() == car_id)
+	}
+
+	fn car_mut(&mut self, car_id: u32) -> Option<&mut wm::LoadCarResponse> {
+		self.cars.iter_mut().find(|car| car.car.car_id() == car_id)
+	}
+
+	pub fn user(&self, user_id: u32) -> Option<&MockUser> {
+		self.users.iter().find(|user| user.user_id == user_id)
+	}
+
+	fn user_mut(&mut self, user_id: u32) -> Option<&mut MockUser> {
+		self.users.iter_mut().find(|user| user.user_id == user_id)
+	}
+
+	fn load_user(&mut self, req: wm::LoadUserRequest) -> wm::LoadUserResponse {
+		let user = self.users.iter().find(|user| {
+			Some(user.access_code.as_str()) == req.access_code.as_deref()
+				|| Some(user.chip_id.as_str()) == req.card_chip_id.as_deref()
+		});
+		let user = match user {
+			Some(user) => user,
+			None => {
+				return wm::LoadUserResponse {
+					error: wm::ErrorCode::ErrNotFound.into(),
+					..Default::default()
+				};
+			}
+		};
+
+		let cars = self
+			.cars
+			.iter()
+			.filter(|car| car.car.user_id() == user.user_id)
+			.take(req.max_cars as usize)
+			.map(|car| car.car.clone())
+			.collect::<Vec<_>>();
+
+		wm::LoadUserResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+			user_id: Some(user.user_id),
+			access_code: Some(user.access_code.clone()),
+			num_of_owned_cars: cars.len() as u32,
+			car_states: cars
+				.iter()
+				.map(|car| wm::load_user_response::CarState {
+					to_be_deleted: self.deleted_car_ids.contains(&car.car_id()),
+					..Default::default()
+				})
+				.collect(),
+			cars,
+			unused_car_tickets: user.car_tickets.clone(),
+			..Default::default()
+		}
+	}
+
+	fn create_user(&mut self, req: wm::CreateUserRequest) -> wm::CreateUserResponse {
+		let user_id = self
+			.users
+			.iter()
+			.map(|user| user.user_id)
+			.max()
+			.unwrap_or(0)
+			+ 1;
+		let access_code = req.access_code.unwrap_or_default();
+		self.users.push(MockUser {
+			user_id,
+			access_code: access_code.clone(),
+			chip_id: req.card_chip_id.unwrap_or_default(),
+			..Default::default()
+		});
+
+		wm::CreateUserResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+			access_code: Some(access_code),
+			user_id,
+			..Default::default()
+		}
+	}
+
+	fn create_car(&mut self, req: wm::CreateCarRequest) -> wm::CreateCarResponse {
+		let user_id = req.user_id();
+		let user = match self.user_mut(user_id) {
+			Some(user) => user,
+			None => {
+				return wm::CreateCarResponse {
+					error: wm::ErrorCode::ErrNotFound.into(),
+					..Default::default()
+				};
+			}
+		};
+		if let Some(user_item_id) = req.user_item_id {
+			let len = user.car_tickets.len();
+			user.car_tickets
+				.retain(|item| item.user_item_id != Some(user_item_id));
+			if user.car_tickets.len() == len {
+				return wm::CreateCarResponse {
+					error: wm::ErrorCode::ErrNotFound.into(),
+					..Default::default()
+				};
+			}
+		}
+		if let Some(deleted_car_id) = req.deleted_car_id {
+			self.cars.retain(|car| car.car.car_id() != deleted_car_id);
+		}
+
+		let car_id = self
+			.cars
+			.iter()
+			.map(|car| car.car.car_id())
+			.max()
+			.unwrap_or(CAR_ID)
+			+ 1;
+		self.cars.push(wm::LoadCarResponse {
+			car: wm::Car {
+				car_id: Some(car_id),
+				user_id: Some(user_id),
+				..req.car
+			},
+			setting: wm::CarSetting {
+				car_id: Some(car_id),
+				transmission: req.transmission,
+				..Default::default()
+			},
+			..Default::default()
+		});
+
+		wm::CreateCarResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+			user_id,
+			car_id,
+			..Default::default()
+		}
+	}
+
+	fn ask_access_code(&mut self, req: wm::AskAccessCodeRequest) -> wm::AskAccessCodeResponse {
+		match self
+			.users
+			.iter()
+			.find(|user| user.chip_id == req.card_chip_id)
+		{
+			Some(user) => wm::AskAccessCodeResponse {
+				error: wm::ErrorCode::ErrSuccess.into(),
+				access_code: user.access_code.clone(),
+			},
+			None => wm::AskAccessCodeResponse {
+				error: wm::ErrorCode::ErrNotFound.into(),
+				..Default::default()
+			},
+		}
+	}
+
+	fn load_car(&mut self, req: wm::LoadCarRequest) -> wm::LoadCarResponse {
+		match self.car(req.car_id) {
+			Some(car) => car.clone(),
+			None => wm::LoadCarResponse {
+				error: wm::ErrorCode::ErrCarNotFound.into(),
+				..Default::default()
+			},
+		}
+	}
+
+	fn load_game_history(
+		&mut self,
+		req: wm::LoadGameHistoryRequest,
+	) -> wm::LoadGameHistoryResponse {
+		if self.car(req.car_id).is_none() {
+			return wm::LoadGameHistoryResponse {
+				error: wm::ErrorCode::ErrCarNotFound.into(),
+				..Default::default()
+			};
+		}
+
+		self.game_histories
+			.get(&req.car_id)
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	fn load_time_attack_record(
+		&mut self,
+		req: wm::LoadTimeAttackRecordRequest,
+	) -> wm::LoadTimeAttackRecordResponse {
+		let mut records = self
+			.ta_records
+			.iter()
+			.filter(|record| record.result.course == req.course)
+			.collect::<Vec<_>>();
+		records.sort_by_key(|record| record.result.time);
+		let model_of = |car_id| self.car(car_id).map(|car| car.car.model());
+
+		let mut res = wm::LoadTimeAttackRecordResponse {
+			whole_ranking: records.iter().map(|record| record.result.time).collect(),
+			model_ranking: records
+				.iter()
+				.filter(|record| model_of(record.car_id) == Some(req.model))
+				.map(|record| record.result.time)
+				.collect(),
+			..Default::default()
+		};
+		if let Some(record) = records
+			.iter()
+			.find(|record| Some(record.car_id) == req.car_id)
+		{
+			let result = &record.result;
+			res.personal_best_time = Some(result.time);
+			res.pb_section_1_time = Some(result.section_1_time);
+			res.pb_section_2_time = Some(result.section_2_time);
+			res.pb_section_3_time = Some(result.section_3_time);
+			res.pb_section_4_time = Some(result.section_4_time);
+			res.pb_section_5_time = result.section_5_time;
+			res.pb_section_6_time = result.section_6_time;
+			res.pb_section_7_time = result.section_7_time;
+		}
+
+		res
+	}
+
+	fn update_car(&mut self, req: wm::UpdateCarRequest) -> wm::UpdateCarResponse {
+		let car = match self.car_mut(req.car_id) {
+			Some(car) => car,
+			None => {
+				return wm::UpdateCarResponse {
+					error: wm::ErrorCode::ErrCarNotFound.into(),
+				};
+			}
+		};
+
+		if let Some(new_car) = req.car {
+			car.car = new_car;
+		}
+		if let Some(setting) = req.setting {
+			car.setting = setting;
+		}
+		for item in req.earned_items {
+			if !car.owned_items.contains(&item) {
+				car.owned_items.push(item);
+			}
+		}
+		match req.to_be_deleted {
+			Some(true) if !self.deleted_car_ids.contains(&req.car_id) => {
+				self.deleted_car_ids.push(req.car_id)
+			}
+			Some(false) => self.deleted_car_ids.retain(|&car_id| car_id != req.car_id),
+			_ => {}
+		}
+
+		wm::UpdateCarResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+		}
+	}
+
+	fn save_game_result(&mut self, req: wm::SaveGameResultRequest) -> wm::SaveGameResultResponse {
+		let car = match self.car_mut(req.car_id) {
+			Some(car) => car,
+			None => {
+				return wm::SaveGameResultResponse {
+					error: wm::ErrorCode::ErrCarNotFound.into(),
+					..Default::default()
+				};
+			}
+		};
+
+		if let Some(new_car) = req.car {
+			car.car = new_car;
+		}
+		if let Some(setting) = req.setting {
+			car.setting = setting;
+		}
+		if let Some(odometer) = req.odometer {
+			car.odometer = odometer;
+		}
+		car.play_count = req.play_count;
+		if !req.preserved_titles.is_empty() {
+			car.earned_titles = req.preserved_titles;
+		}
+		for item in req.earned_items {
+			if !car.owned_items.contains(&item) {
+				car.owned_items.push(item);
+			}
+		}
+		if let Some(vs) = req.vs_result {
+			car.vs_play_count = vs.vs_play_count;
+			car.vs_burst_count = vs.vs_burst_count.unwrap_or(car.vs_burst_count);
+			car.vs_star_count = vs.vs_star_count.unwrap_or(car.vs_star_count);
+			car.vs_star_count_max = vs.vs_star_count_max.unwrap_or(car.vs_star_count_max);
+			car.vs_cool_or_wild = vs.vs_cool_or_wild.unwrap_or(car.vs_cool_or_wild);
+			car.vs_smooth_or_rough = vs.vs_smooth_or_rough.unwrap_or(car.vs_smooth_or_rough);
+			car.vs_triple_star_medals = vs
+				.vs_triple_star_medals
+				.unwrap_or(car.vs_triple_star_medals);
+			car.vs_double_star_medals = vs
+				.vs_double_star_medals
+				.unwrap_or(car.vs_double_star_medals);
+			car.vs_single_star_medals = vs
+				.vs_single_star_medals
+				.unwrap_or(car.vs_single_star_medals);
+			car.vs_plain_medals = vs.vs_plain_medals.unwrap_or(car.vs_plain_medals);
+		}
+		if let Some(st) = req.st_result {
+			car.st_play_count = st.st_play_count;
+			car.tuning_point = st.tuning_point.unwrap_or(car.tuning_point);
+			car.st_clear_bits = st.st_clear_bits.unwrap_or(car.st_clear_bits);
+			car.st_clear_div_count = st.st_clear_div_count.unwrap_or(car.st_clear_div_count);
+			car.st_clear_count = st.st_clear_count.unwrap_or(car.st_clear_count);
+			car.st_lose_bits = st.st_lose_bits.unwrap_or(car.st_lose_bits);
+			car.st_consecutive_wins = st.st_consecutive_wins.unwrap_or(car.st_consecutive_wins);
+			car.st_completed_100_episodes = st
+				.st_completed_100_episodes
+				.unwrap_or(car.st_completed_100_episodes);
+		}
+		if let Some(rg) = req.rg_result {
+			car.rg_play_count = rg.rg_play_count;
+			car.rg_trophy = rg.rg_trophy.unwrap_or(car.rg_trophy);
+			car.rg_stamp = rg.rg_stamp.unwrap_or(car.rg_stamp);
+			car.rg_acquire_all_crowns = rg.acquire_all_crowns.unwrap_or(car.rg_acquire_all_crowns);
+			car.dressup_level = rg.dressup_level.unwrap_or(car.dressup_level);
+			car.dressup_point = rg.dressup_point.unwrap_or(car.dressup_point);
+			if !rg.rg_region_map_score.is_empty() {
+				car.rg_region_map_score = rg.rg_region_map_score;
+			}
+			if let Some(highway) = rg.highway_result {
+				car.rg_highway_clear_count = highway.rg_highway_clear_count;
+				car.rg_highway_point = highway.rg_highway_point;
+				car.rg_highway_station_clear_bits = highway.rg_highway_station_clear_bits;
+				car.rg_highway_previous_dice = highway.rg_highway_previous_dice;
+			}
+		}
+
+		let user_id = car.car.user_id();
+		let mut next_user_item_id = self.next_user_item_id;
+		if let Some(user) = self.user_mut(user_id) {
+			for mut item in req.earned_user_items {
+				item.user_item_id = Some(next_user_item_id);
+				next_user_item_id += 1;
+				if is_car_ticket(&item) {
+					user.car_tickets.push(item);
+				} else {
+					user.unreceived_items.push(item);
+				}
+			}
+		}
+		self.next_user_item_id = next_user_item_id;
+
+		// Only a faster time replaces the personal best
+		if let Some(ta) = req.ta_result {
+			match self
+				.ta_records
+				.iter_mut()
+				.find(|record| record.car_id == req.car_id && record.result.course == ta.course)
+			{
+				Some(record) if record.result.time <= ta.time => {}
+				Some(record) => record.result = ta,
+				None => self.ta_records.push(MockTaRecord {
+					car_id: req.car_id,
+					result: ta,
+				}),
+			}
+		}
+
+		wm::SaveGameResultResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+			..Default::default()
+		}
+	}
+
+	fn load_scratch_information(
+		&mut self,
+		req: wm::LoadScratchInformationRequest,
+	) -> wm::LoadScratchInformationResponse {
+		match self.user(req.user_id) {
+			Some(user) => scratch_information(user),
+			None => wm::LoadScratchInformationResponse {
+				error: wm::ErrorCode::ErrNotFound.into(),
+				..Default::default()
+			},
+		}
+	}
+
+	fn save_scratch_sheet(
+		&mut self,
+		req: wm::SaveScratchSheetRequest,
+	) -> wm::SaveScratchSheetResponse {
+		let user_item_id = self.next_user_item_id;
+		let Some(user) = self.user_mut(req.user_id) else {
+			return wm::SaveScratchSheetResponse {
+				error: wm::ErrorCode::ErrNotFound.into(),
+				..Default::default()
+			};
+		};
+		let error = |code: wm::ErrorCode| wm::SaveScratchSheetResponse {
+			error: code.into(),
+			..Default::default()
+		};
+		if user.scratch_locked {
+			return error(wm::ErrorCode::ErrScratchLocked);
+		}
+		if req.target_sheet != user.current_sheet {
+			return error(wm::ErrorCode::ErrRequest);
+		}
+		let Some(square) = user
+			.scratch_sheets
+			.get_mut(req.target_sheet as usize)
+			.and_then(|sheet| sheet.squares.get_mut(req.target_square as usize))
+			.filter(|square| !square.earned)
+		else {
+			return error(wm::ErrorCode::ErrRequest);
+		};
+
+		square.earned = true;
+		let item = wm::UserItem {
+			category: square.category,
+			item_id: square.item_id,
+			user_item_id: Some(user_item_id),
+			earned_at: Some(req.timestamp),
+			..Default::default()
+		};
+		user.scratch_items.push(item.clone());
+		user.unreceived_items.push(item.clone());
+		let info = scratch_information(user);
+		self.next_user_item_id += 1;
+
+		wm::SaveScratchSheetResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+			scratch_sheets: info.scratch_sheets,
+			current_sheet: info.current_sheet,
+			num_of_scratched: info.num_of_scratched,
+			earned_item: Some(item),
+		}
+	}
+
+	fn turn_scratch_sheet(
+		&mut self,
+		req: wm::TurnScratchSheetRequest,
+	) -> wm::TurnScratchSheetResponse {
+		let error = match self.user_mut(req.user_id) {
+			None => wm::ErrorCode::ErrNotFound,
+			Some(user) if user.scratch_locked => wm::ErrorCode::ErrScratchLocked,
+			Some(user)
+				if req.target_sheet != user.current_sheet + 1
+					|| req.target_sheet as usize >= user.scratch_sheets.len() =>
+			{
+				wm::ErrorCode::ErrRequest
+			}
+			Some(user) => {
+				user.current_sheet = req.target_sheet;
+				wm::ErrorCode::ErrSuccess
+			}
+		};
+
+		wm::TurnScratchSheetResponse {
+			error: error.into(),
+		}
+	}
+
+	fn load_unreceived_user_items(
+		&mut self,
+		req: wm::LoadUnreceivedUserItemsRequest,
+	) -> wm::LoadUnreceivedUserItemsResponse {
+		match self.user(req.user_id) {
+			Some(user) => wm::LoadUnreceivedUserItemsResponse {
+				error: wm::ErrorCode::ErrSuccess.into(),
+				owned_user_items: user.unreceived_items.clone(),
+			},
+			None => wm::LoadUnreceivedUserItemsResponse {
+				error: wm::ErrorCode::ErrNotFound.into(),
+				..Default::default()
+			},
+		}
+	}
+
+	fn check_item_receivable_cars(
+		&mut self,
+		req: wm::CheckItemReceivableCarsRequest,
+	) -> wm::CheckItemReceivableCarsResponse {
+		if self.user(req.user_id).is_none() {
+			return wm::CheckItemReceivableCarsResponse {
+				error: wm::ErrorCode::ErrNotFound.into(),
+				..Default::default()
+			};
+		}
+
+		let car_ids = self
+			.cars
+			.iter()
+			.filter(|car| car.car.user_id() == req.user_id)
+			.filter(|car| {
+				req.items_to_be_earned
+					.iter()
+					.all(|item| receivable(car, item))
+			})
+			.map(|car| car.car.car_id())
+			.collect();
+
+		wm::CheckItemReceivableCarsResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+			car_ids,
+		}
+	}
+
+	/// `target_item_ids` are user item ids, received items leave the unreceived ones.
+	fn receive_user_items(
+		&mut self,
+		req: wm::ReceiveUserItemsRequest,
+	) -> wm::ReceiveUserItemsResponse {
+		let error = |code: wm::ErrorCode| wm::ReceiveUserItemsResponse { error: code.into() };
+		// Borrowed straight from the fields so the car and its user can both be changed
+		let Some(car) = self
+			.cars
+			.iter_mut()
+			.find(|car| car.car.car_id() == req.car_id)
+		else {
+			return error(wm::ErrorCode::ErrCarNotFound);
+		};
+		let Some(user) = self
+			.users
+			.iter_mut()
+			.find(|user| user.user_id == car.car.user_id())
+		else {
+			return error(wm::ErrorCode::ErrNotFound);
+		};
+		let all_receivable = req.target_item_ids.iter().all(|user_item_id| {
+			user.unreceived_items
+				.iter()
+				.find(|item| item.user_item_id == Some(*user_item_id))
+				.is_some_and(|item| receivable(car, item))
+		});
+		if !all_receivable {
+			return error(wm::ErrorCode::ErrRequest);
+		}
+
+		for user_item_id in req.target_item_ids {
+			let index = user
+				.unreceived_items
+				.iter()
+				.position(|item| item.user_item_id == Some(user_item_id))
+				.unwrap();
+			let item = user.unreceived_items.remove(index);
+			if item.category() == wm::ItemCategory::CatCarTitle {
+				car.earned_titles.push(item.title_name().to_string());
+			} else {
+				car.owned_items.push(wm::CarItem {
+					category: item.category,
+					item_id: item.item_id,
+					amount: Some(1),
+				});
+			}
+		}
+
+		error(wm::ErrorCode::ErrSuccess)
+	}
+
+	/// Only `car_order` is kept, cars it leaves out go after the ones it lists.
+	fn save_terminal_result(
+		&mut self,
+		req: wm::SaveTerminalResultRequest,
+	) -> wm::SaveTerminalResultResponse {
+		if self.user(req.user_id).is_none() {
+			return wm::SaveTerminalResultResponse {
+				error: wm::ErrorCode::ErrNotFound.into(),
+			};
+		}
+
+		self.cars.sort_by_key(|car| {
+			req.car_order
+				.iter()
+				.position(|&car_id| car_id == car.car.car_id())
+				.unwrap_or(req.car_order.len())
+		});
+
+		wm::SaveTerminalResultResponse {
+			error: wm::ErrorCode::ErrSuccess.into(),
+		}
+	}
+
+	fn dispatch(&mut self, path: &str, body: &[u8]) -> Option<Vec<u8>> {
+		let res = match path {
+			"/wmmt6/method/load_user" => {
+				self.load_user(Message::decode(body).ok()?).encode_to_vec()
+			}
+			"/wmmt6/method/create_user" => self
+				.create_user(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/create_car" => {
+				self.create_car(Message::decode(body).ok()?).encode_to_vec()
+			}
+			"/wmmt6/method/ask_access_code" => self
+				.ask_access_code(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/load_car" => self.load_car(Message::decode(body).ok()?).encode_to_vec(),
+			"/wmmt6/method/load_game_history" => self
+				.load_game_history(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/load_time_attack_record" => self
+				.load_time_attack_record(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/update_car" => {
+				self.update_car(Message::decode(body).ok()?).encode_to_vec()
+			}
+			"/wmmt6/method/save_game_result" => self
+				.save_game_result(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/load_scratch_information" => self
+				.load_scratch_information(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/save_scratch_sheet" => self
+				.save_scratch_sheet(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/turn_scratch_sheet" => self
+				.turn_scratch_sheet(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/load_unreceived_user_items" => self
+				.load_unreceived_user_items(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/check_item_receivable_cars" => self
+				.check_item_receivable_cars(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/receive_user_items" => self
+				.receive_user_items(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			"/wmmt6/method/save_terminal_result" => self
+				.save_terminal_result(Message::decode(body).ok()?)
+				.encode_to_vec(),
+			_ => return None,
+		};
+		Some(res)
+	}
+}
+
+fn is_car_ticket(item: &wm::UserItem) -> bool {
+	matches!(
+		item.category(),
+		wm::ItemCategory::CatCarTicket | wm::ItemCategory::CatCarTicketFree
+	)
+}
+
+fn receivable(car: &wm::LoadCarResponse, item: &wm::UserItem) -> bool {
+	match item.category() {
+		wm::ItemCategory::CatCarTicket
+		| wm::ItemCategory::CatCarTicketFree
+		| wm::ItemCategory::CatConsumptionItem => false,
+		wm::ItemCategory::CatCarTitle => !car.earned_titles.iter().any(|t| t == item.title_name()),
+		_ => !car
+			.owned_items
+			.iter()
+			.any(|owned| owned.category == item.category && owned.item_id == item.item_id),
+	}
+}
+
+fn scratch_information(user: &MockUser) -> wm::LoadScratchInformationResponse {
+	let num_of_scratched = user
+		.scratch_sheets
+		.get(user.current_sheet as usize)
+		.map_or(0, |sheet| {
+			sheet.squares.iter().filter(|square| square.earned).count() as u32
+		});
+
+	wm::LoadScratchInformationResponse {
+		error: wm::ErrorCode::ErrSuccess.into(),
+		scratch_sheets: user.scratch_sheets.clone(),
+		current_sheet: user.current_sheet,
+		num_of_scratched,
+		owned_user_items: user.scratch_items.clone(),
+	}
+}
+
+pub struct MockServer {
+	pub url: Url,
+	pub state: Arc<Mutex<MockState>>,
+}
+
+impl MockServer {
+	pub async fn start(state: MockState) -> Self {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+		let state = Arc::new(Mutex::new(state));
+
+		let server_state = state.clone();
+		tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				tokio::spawn(serve(stream, server_state.clone()));
+			}
+		});
+
+		Self { url, state }
+	}
+
+	pub fn client(&self) -> client::WmClient {
+		let endpoint = endpoint::ServerEndpoint::parse(self.url.as_str()).unwrap();
+		client::WmClient::new(&endpoint).unwrap()
+	}
+}
+
+/// Just enough HTTP/1.1 to answer reqwest: keep-alive and `content-length` bodies.
+async fn serve(mut stream: TcpStream, state: Arc<Mutex<MockState>>) {
+	let mut buf = Vec::new();
+	loop {
+		let header_end = loop {
+			if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+				break pos + 4;
+			}
+			let mut chunk = [0; 4096];
+			match stream.read(&mut chunk).await {
+				Ok(0) | Err(_) => return,
+				Ok(n) => buf.extend_from_slice(&chunk[..n]),
+			}
+		};
+
+		let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+		let path = head
+			.split_whitespace()
+			.nth(1)
+			.unwrap_or_default()
+			.to_string();
+		let content_length = head
+			.lines()
+			.filter_map(|line| line.split_once(':'))
+			.find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+			.and_then(|(_, value)| value.trim().parse().ok())
+			.unwrap_or(0);
+
+		while buf.len() < header_end + content_length {
+			let mut chunk = [0; 4096];
+			match stream.read(&mut chunk).await {
+				Ok(0) | Err(_) => return,
+				Ok(n) => buf.extend_from_slice(&chunk[..n]),
+			}
+		}
+		let body = buf[header_end..header_end + content_length].to_vec();
+		buf.drain(..header_end + content_length);
+
+		let res = state.lock().unwrap().dispatch(&path, &body);
+		let (status, body) = match res {
+			Some(body) => ("200 OK", body),
+			None => ("404 Not Found", vec![]),
+		};
+		let head = format!(
+			"HTTP/1.1 {status}\r\ncontent-type: application/x-protobuf\r\ncontent-length: {}\r\n\r\n",
+			body.len()
+		);
+		if stream.write_all(head.as_bytes()).await.is_err()
+			|| stream.write_all(&body).await.is_err()
+		{
+			return;
+		}
+	}
+}
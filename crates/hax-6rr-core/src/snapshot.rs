@@ -0,0 +1,228 @@
+//! Versioned backups of a car as returned by `load_car`.
+use crate::diff::{diff, Change};
+use crate::{client, wm};
+use anyhow::{bail, Context, Result};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the JSON layout changes in a way older builds can't read.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarSnapshot {
+	pub version: u32,
+	/// Unix time in seconds, unknown for protobuf files since the reply has nowhere to keep it.
+	pub exported_at: Option<u64>,
+	pub car: wm::LoadCarResponse,
+}
+
+impl CarSnapshot {
+	pub fn new(car: wm::LoadCarResponse) -> Self {
+		let exported_at = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.map(|time| time.as_secs())
+			.ok();
+
+		Self {
+			version: SNAPSHOT_VERSION,
+			exported_at,
+			car,
+		}
+	}
+
+	pub fn car_id(&self) -> u32 {
+		self.car.car.car_id()
+	}
+
+	pub fn to_json(&self) -> Result<String> {
+		Ok(serde_json::to_string_pretty(self)?)
+	}
+
+	pub fn from_json(json: &str) -> Result<Self> {
+		#[derive(Deserialize)]
+		struct Version {
+			version: u32,
+		}
+
+		let version: Version = serde_json::from_str(json)?;
+		if version.version > SNAPSHOT_VERSION {
+			bail!(
+				"Snapshot version {} is newer than supported version {SNAPSHOT_VERSION}",
+				version.version
+			);
+		}
+		Ok(serde_json::from_str(json)?)
+	}
+
+	/// The `LoadCarResponse` exactly as it goes over the wire.
+	pub fn to_protobuf(&self) -> Vec<u8> {
+		self.car.encode_to_vec()
+	}
+
+	pub fn from_protobuf(bytes: &[u8]) -> Result<Self> {
+		Ok(Self {
+			version: SNAPSHOT_VERSION,
+			exported_at: None,
+			car: wm::LoadCarResponse::decode(bytes)?,
+		})
+	}
+
+	/// Writes `<path>.json` and `<path>.pb`, returning both paths.
+	pub fn save(&self, path: &Path) -> Result<(PathBuf, PathBuf)> {
+		let json_path = path.with_extension("json");
+		let protobuf_path = path.with_extension("pb");
+		std::fs::write(&json_path, self.to_json()?)?;
+		std::fs::write(&protobuf_path, self.to_protobuf())?;
+
+		Ok((json_path, protobuf_path))
+	}
+
+	/// Reads either file written by `save`, telling them apart by extension.
+	pub fn load(path: &Path) -> Result<Self> {
+		let bytes =
+			std::fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+		match path.extension().and_then(|ext| ext.to_str()) {
+			Some("pb") => Self::from_protobuf(&bytes),
+			_ => Self::from_json(std::str::from_utf8(&bytes)?),
+		}
+	}
+
+	/// Fields of `current` that restoring this snapshot would overwrite.
+	pub fn changes(&self, current: &wm::LoadCarResponse) -> Result<Vec<Change>> {
+		diff(current, &self.car)
+	}
+
+	/// Whether restoring `changes` has to go through `save_game_result`, which the server
+	/// records as a played VS battle. Only VS progress needs it.
+	pub fn records_game_result(changes: &[Change]) -> bool {
+		changes.iter().any(|change| change.field.starts_with("vs_"))
+	}
+
+	/// Pushes the snapshot back through `update_car`, and `save_game_result` when `changes`
+	/// include VS progress. Odometer and play count only go back along with it.
+	///
+	/// Items can only be granted, so anything owned now but missing from the snapshot stays.
+	pub async fn restore(&self, client: &client::WmClient, changes: &[Change]) -> Result<()> {
+		let car = &self.car;
+
+		client
+			.update_car(wm::UpdateCarRequest {
+				car_id: self.car_id(),
+				car: Some(car.car.clone()),
+				setting: Some(car.setting.clone()),
+				earned_items: car.owned_items.clone(),
+				..Default::default()
+			})
+			.await?;
+
+		if !Self::records_game_result(changes) {
+			return Ok(());
+		}
+		client
+			.save_game_result(wm::SaveGameResultRequest {
+				car_id: self.car_id(),
+				game_mode: wm::GameMode::ModeVsBattle.into(),
+				played_at: car.car.last_played_at(),
+				play_count: car.play_count,
+				car: Some(car.car.clone()),
+				setting: Some(car.setting.clone()),
+				odometer: Some(car.odometer),
+				earned_custom_color: Some(car.earned_custom_color),
+				retired: false,
+				vs_result: Some(wm::save_game_result_request::VersusBattleResult {
+					result: 0,
+					survived: true,
+					num_of_players: 0,
+					area: 0,
+					is_morning: false,
+					vs_play_count: car.vs_play_count,
+					vs_burst_count: Some(car.vs_burst_count),
+					vs_star_count: Some(car.vs_star_count),
+					vs_star_count_max: Some(car.vs_star_count_max),
+					vs_cool_or_wild: Some(car.vs_cool_or_wild),
+					vs_smooth_or_rough: Some(car.vs_smooth_or_rough),
+					vs_triple_star_medals: Some(car.vs_triple_star_medals),
+					vs_double_star_medals: Some(car.vs_double_star_medals),
+					vs_single_star_medals: Some(car.vs_single_star_medals),
+					vs_plain_medals: Some(car.vs_plain_medals),
+					..Default::default()
+				}),
+				..Default::default()
+			})
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn snapshot() -> CarSnapshot {
+		CarSnapshot::new(wm::LoadCarResponse {
+			car: wm::Car {
+				car_id: Some(100),
+				wheel: 3,
+				..Default::default()
+			},
+			odometer: 1000,
+			owned_items: vec![wm::CarItem {
+				category: wm::ItemCategory::CatBgm.into(),
+				item_id: 1,
+				amount: Some(1),
+			}],
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn json_and_protobuf_round_trip() {
+		let snapshot = snapshot();
+
+		let json = CarSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+		assert_eq!(json, snapshot);
+
+		let protobuf = CarSnapshot::from_protobuf(&snapshot.to_protobuf()).unwrap();
+		assert_eq!(protobuf.car, snapshot.car);
+		assert_eq!(protobuf.exported_at, None);
+	}
+
+	#[test]
+	fn rejects_newer_versions() {
+		let mut snapshot = snapshot();
+		snapshot.version = SNAPSHOT_VERSION + 1;
+
+		assert!(CarSnapshot::from_json(&snapshot.to_json().unwrap()).is_err());
+	}
+
+	#[test]
+	fn changes_list_differing_fields() {
+		let snapshot = snapshot();
+		let mut current = snapshot.car.clone();
+		current.car.wheel = 0;
+		current.odometer = 2000;
+		current.owned_items.clear();
+
+		let changes = snapshot.changes(&current).unwrap();
+		let fields = changes
+			.iter()
+			.map(|change| change.field.as_str())
+			.collect::<Vec<_>>();
+		assert_eq!(fields.len(), 3);
+		assert!(fields.contains(&"car.wheel"));
+		assert!(fields.contains(&"odometer"));
+		assert!(fields.contains(&"owned_items"));
+		assert!(changes.contains(&Change {
+			field: String::from("odometer"),
+			old: String::from("2000"),
+			new: String::from("1000"),
+		}));
+		assert!(!CarSnapshot::records_game_result(&changes));
+
+		current.vs_plain_medals = 3;
+		let changes = snapshot.changes(&current).unwrap();
+		assert!(CarSnapshot::records_game_result(&changes));
+	}
+}
@@ -0,0 +1,250 @@
+use crate::*;
+use anyhow::{bail, Result};
+use diff::Change;
+use eframe::egui;
+use snapshot::CarSnapshot;
+use std::path::PathBuf;
+
+#[derive(Default)]
+pub struct BackupMenu {
+	pub status: Option<String>,
+	pub preview: Option<(CarSnapshot, Vec<Change>)>,
+	pub error: Option<String>,
+	pub pending_export: Option<task::Task<String>>,
+	pub pending_preview: Option<task::Task<(CarSnapshot, Vec<Change>)>>,
+	pub pending_restore: Option<task::Task<wm::LoadCarResponse>>,
+}
+
+impl BackupMenu {
+	/// Returns the car as the server has it after a restore went through.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		car_id: u32,
+	) -> Option<wm::LoadCarResponse> {
+		match task::poll(&mut self.pending_export) {
+			Some(Ok(status)) => {
+				self.status = Some(status);
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+		match task::poll(&mut self.pending_preview) {
+			Some(Ok(preview)) => {
+				self.preview = Some(preview);
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+		let restored = match task::poll(&mut self.pending_restore) {
+			Some(Ok(car)) => {
+				self.preview = None;
+				self.status = Some(String::from("Snapshot restored"));
+				self.error = None;
+				Some(car)
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		let busy = self.pending_export.is_some()
+			|| self.pending_preview.is_some()
+			|| self.pending_restore.is_some();
+
+		ui.add_enabled_ui(!busy, |ui| {
+			if ui.button("Export car").clicked() {
+				let client = client.clone();
+				self.status = None;
+				self.error = None;
+				self.pending_export = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+					export_car(&client, car_id).await
+				}));
+			}
+
+			ui.separator();
+
+			let mut cancel_clicked = false;
+			if let Some((snapshot, changes)) = &self.preview {
+				ui.heading("Restore preview");
+				if changes.is_empty() {
+					ui.label("The snapshot matches the car on the server");
+				}
+				if CarSnapshot::records_game_result(changes) {
+					ui.colored_label(
+						egui::Color32::YELLOW,
+						"Restoring VS progress saves a game result, which the server records as a played VS battle",
+					);
+				} else if changes
+					.iter()
+					.any(|change| change.field == "odometer" || change.field == "play_count")
+				{
+					ui.label("Odometer and play count are only restored along with VS progress");
+				}
+				egui::Grid::new("BackupGrid").num_columns(3).show(ui, |ui| {
+					for change in changes.iter() {
+						ui.label(&change.field);
+						ui.label(&change.old);
+						ui.label(&change.new);
+						ui.end_row();
+					}
+				});
+
+				ui.horizontal(|ui| {
+					if ui.button("Restore").clicked() {
+						let client = client.clone();
+						let snapshot = snapshot.clone();
+						let changes = changes.clone();
+						self.error = None;
+						self.pending_restore =
+							Some(task::Task::spawn(runtime, ui.ctx(), async move {
+								snapshot.restore(&client, &changes).await?;
+								load_car(snapshot.car_id(), &client).await
+							}));
+					}
+					if ui.button("Cancel").clicked() {
+						cancel_clicked = true;
+					}
+				});
+			} else {
+				ui.heading("Drop a snapshot onto window to restore it");
+
+				let dropped = ui.ctx().input(|i| i.raw.dropped_files.clone());
+				if let Some(path) = dropped.into_iter().find_map(|file| file.path) {
+					let client = client.clone();
+					self.status = None;
+					self.error = None;
+					self.pending_preview = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+						preview_restore(&client, car_id, path).await
+					}));
+				}
+			}
+			if cancel_clicked {
+				self.preview = None;
+			}
+		});
+
+		if busy {
+			ui.spinner();
+		}
+		if let Some(status) = &self.status {
+			ui.label(status);
+		}
+		show_error(ui, &self.error);
+
+		restored
+	}
+
+	pub fn back(&mut self) -> bool {
+		if self.preview.is_some() {
+			self.preview = None;
+			false
+		} else {
+			true
+		}
+	}
+}
+
+/// Saves what the server currently has, not the unsaved edits in the other menus.
+async fn export_car(client: &client::WmClient, car_id: u32) -> Result<String> {
+	let snapshot = CarSnapshot::new(load_car(car_id, client).await?);
+	let (json_path, protobuf_path) = snapshot.save(&PathBuf::from(format!(
+		"car_{car_id}_{}",
+		snapshot.exported_at.unwrap_or_default()
+	)))?;
+
+	Ok(format!(
+		"Exported to {} and {}",
+		json_path.display(),
+		protobuf_path.display()
+	))
+}
+
+async fn preview_restore(
+	client: &client::WmClient,
+	car_id: u32,
+	path: PathBuf,
+) -> Result<(CarSnapshot, Vec<Change>)> {
+	let snapshot = CarSnapshot::load(&path)?;
+	if snapshot.car_id() != car_id {
+		bail!(
+			"Snapshot is of car {}, not the selected car {car_id}",
+			snapshot.car_id()
+		);
+	}
+	let current = load_car(car_id, client).await?;
+	let changes = snapshot.changes(&current)?;
+
+	Ok((snapshot, changes))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn restore_brings_back_exported_car() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let snapshot = CarSnapshot::new(load_car(mock::CAR_ID, &client).await.unwrap());
+		let path = std::env::temp_dir().join(format!("hax-6rr-{}-backup", std::process::id()));
+		let (json_path, protobuf_path) = snapshot.save(&path).unwrap();
+
+		{
+			let mut state = server.state.lock().unwrap();
+			let car = state.cars.first_mut().unwrap();
+			car.car.wheel = 5;
+			car.odometer = 9999;
+			car.vs_triple_star_medals = 7;
+		}
+
+		let (restore, changes) = preview_restore(&client, mock::CAR_ID, json_path.clone())
+			.await
+			.unwrap();
+		assert_eq!(changes.len(), 3);
+		assert!(
+			preview_restore(&client, mock::CAR_ID + 1, protobuf_path.clone())
+				.await
+				.is_err()
+		);
+
+		restore.restore(&client, &changes).await.unwrap();
+		_ = std::fs::remove_file(json_path);
+		_ = std::fs::remove_file(protobuf_path);
+
+		let state = server.state.lock().unwrap();
+		let car = state.car(mock::CAR_ID).unwrap();
+		assert_eq!(car.car.wheel, 0);
+		assert_eq!(car.odometer, 1000);
+		assert_eq!(car.vs_triple_star_medals, 0);
+	}
+
+	#[tokio::test]
+	async fn restore_without_vs_changes_skips_game_result() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let snapshot = CarSnapshot::new(load_car(mock::CAR_ID, &client).await.unwrap());
+		{
+			let mut state = server.state.lock().unwrap();
+			let car = state.cars.first_mut().unwrap();
+			car.car.wheel = 5;
+			car.odometer = 9999;
+		}
+
+		let current = load_car(mock::CAR_ID, &client).await.unwrap();
+		let changes = snapshot.changes(&current).unwrap();
+		assert!(!CarSnapshot::records_game_result(&changes));
+		snapshot.restore(&client, &changes).await.unwrap();
+
+		let state = server.state.lock().unwrap();
+		let car = state.car(mock::CAR_ID).unwrap();
+		assert_eq!(car.car.wheel, 0);
+		assert_eq!(car.odometer, 9999);
+	}
+}
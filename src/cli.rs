@@ -0,0 +1,269 @@
+//! Headless mode for scripting card edits without the GUI.
+use crate::*;
+use anyhow::{bail, Context};
+
+const USAGE: &str = "\
+Usage: hax-6rr <server> <card.ini> <command> [args...]
+
+Commands:
+  list                           List the cars on the card
+  dump <car id>                  Print a car as JSON
+  give <car id> <category>...    Grant every missing item of the categories
+                                 (aura, bgm, meter, nameplate, rival-marker,
+                                 custom-frame, color, dress-up, all)
+  set <car id> <key=value>...    Change car settings and save them";
+
+const CATEGORIES: &[(&str, wm::ItemCategory)] = &[
+	("aura", wm::ItemCategory::CatAuraMotif),
+	("bgm", wm::ItemCategory::CatBgm),
+	("meter", wm::ItemCategory::CatMeter),
+	("nameplate", wm::ItemCategory::CatNamePlate),
+	("rival-marker", wm::ItemCategory::CatRivalMarker),
+	("custom-frame", wm::ItemCategory::CatCustomFrame),
+	("color", wm::ItemCategory::CatCustomColor),
+	("dress-up", wm::ItemCategory::CatAero),
+];
+
+pub fn run(args: &[String]) -> Result<()> {
+	let (server, ini_path, command) = match args {
+		[server, ini_path, command @ ..] if !command.is_empty() => (server, ini_path, command),
+		_ => bail!("{USAGE}"),
+	};
+	let client = client::WmClient::new(&endpoint::ServerEndpoint::parse(server)?)?;
+
+	let runtime = tokio::runtime::Builder::new_multi_thread()
+		.enable_all()
+		.build()?;
+	let output = runtime.block_on(execute(&client, ini_path, command))?;
+	print!("{output}");
+
+	Ok(())
+}
+
+async fn execute(client: &client::WmClient, ini_path: &str, command: &[String]) -> Result<String> {
+	let user = load_user(ini_path, client).await?;
+
+	match command {
+		[cmd] if cmd == "list" => {
+			let mut output = String::new();
+			for car in user.cars.iter() {
+				let model = wm::Cars::from_u32(car.visual_model())
+					.map(|model| model.to_string())
+					.unwrap_or_default();
+				output += &format!("{}\t{}\t{}\n", car.car_id(), car.name(), model);
+			}
+			Ok(output)
+		}
+		[cmd, car_id] if cmd == "dump" => {
+			let car = load_owned_car(client, &user, car_id).await?;
+			Ok(serde_json::to_string_pretty(&car)? + "\n")
+		}
+		[cmd, car_id, categories @ ..] if cmd == "give" && !categories.is_empty() => {
+			let car = load_owned_car(client, &user, car_id).await?;
+			let cars = wm::Cars::from_u32(car.car.visual_model()).context("Unknown car model")?;
+
+			let mut categories = categories
+				.iter()
+				.map(|name| parse_categories(name))
+				.collect::<Result<Vec<_>>>()?
+				.concat();
+			categories.sort();
+			categories.dedup();
+
+			let mut output = String::new();
+			let mut car_items = car.owned_items.clone();
+			for category in categories {
+				let mut items = items::missing_items(category, &cars, &car_items);
+				if items.is_empty() {
+					continue;
+				}
+				items::update_car_items(client, car.car.clone(), None, items.clone()).await?;
+				output += &format!(
+					"{}: granted {} items\n",
+					category.as_str_name(),
+					items.len()
+				);
+				car_items.append(&mut items);
+			}
+			Ok(output)
+		}
+		[cmd, car_id, settings @ ..] if cmd == "set" && !settings.is_empty() => {
+			let car = load_owned_car(client, &user, car_id).await?;
+			let mut new_car = car.car.clone();
+			let mut setting = car.setting.clone();
+			for pair in settings {
+				let (key, value) = pair
+					.split_once('=')
+					.with_context(|| format!("Expected key=value, got {pair}"))?;
+				apply_setting(&mut new_car, &mut setting, key, value)?;
+			}
+			items::update_car_items(client, new_car, Some(setting), vec![]).await?;
+			Ok(format!(
+				"{}: saved {} settings\n",
+				car.car.car_id(),
+				settings.len()
+			))
+		}
+		_ => bail!("{USAGE}"),
+	}
+}
+
+async fn load_owned_car(
+	client: &client::WmClient,
+	user: &wm::LoadUserResponse,
+	car_id: &str,
+) -> Result<wm::LoadCarResponse> {
+	let car_id = car_id.parse().context("Invalid car id")?;
+	if !user.cars.iter().any(|car| car.car_id() == car_id) {
+		bail!("Car {car_id} is not on this card");
+	}
+	load_car(car_id, client).await
+}
+
+fn parse_categories(name: &str) -> Result<Vec<wm::ItemCategory>> {
+	if name == "all" {
+		return Ok(CATEGORIES.iter().map(|(_, category)| *category).collect());
+	}
+	CATEGORIES
+		.iter()
+		.find(|(category_name, _)| *category_name == name)
+		.map(|(_, category)| vec![*category])
+		.with_context(|| format!("Unknown item category {name}"))
+}
+
+fn apply_setting(
+	car: &mut wm::Car,
+	setting: &mut wm::CarSetting,
+	key: &str,
+	value: &str,
+) -> Result<()> {
+	fn number(value: &str) -> Result<u32> {
+		value
+			.parse()
+			.with_context(|| format!("Expected a number, got {value}"))
+	}
+
+	fn flag(value: &str) -> Result<bool> {
+		match value {
+			"1" | "true" | "on" => Ok(true),
+			"0" | "false" | "off" => Ok(false),
+			_ => bail!("Expected true or false, got {value}"),
+		}
+	}
+
+	match key {
+		"name" => car.name = Some(String::from(value)),
+		"title" => car.title = String::from(value),
+		"level" => car.level = number(value)?,
+		"region" => car.region_id = Some(number(value)?),
+		"plate_number" => car.plate_number = number(value)?.clamp(0, 9999),
+		"default_color" => car.default_color = Some(number(value)?),
+		"custom_color" => car.custom_color = number(value)?,
+		"wheel" => car.wheel = number(value)?,
+		"wheel_color" => car.wheel_color = number(value)?,
+		"aero" => car.aero = number(value)?,
+		"bonnet" => car.bonnet = number(value)?,
+		"wing" => car.wing = number(value)?,
+		"mirror" => car.mirror = number(value)?,
+		"neon" => car.neon = number(value)?,
+		"trunk" => car.trunk = number(value)?,
+		"plate" => car.plate = number(value)?,
+		"plate_color" => car.plate_color = number(value)?,
+		"aura_motif" => car.aura_motif = Some(number(value)?),
+		"rival_marker" => car.rival_marker = Some(number(value)?),
+		"window_decoration" => car.window_decoration = Some(number(value)?),
+		"custom_frame" => car.custom_frame = Some(number(value)?),
+		"volume" => setting.volume = number(value)?.clamp(0, 3),
+		"bgm" => setting.bgm = number(value)?,
+		"meter" => setting.meter = number(value)?,
+		"nameplate" => setting.nameplate = number(value)?,
+		"nameplate_color" => setting.nameplate_color = number(value)?,
+		"terminal_background" => setting.terminal_background = number(value)?,
+		"navigation_map" => setting.navigation_map = flag(value)?,
+		"retire" => setting.retire = flag(value)?,
+		"transmission" => setting.transmission = flag(value)?,
+		"view" => setting.view = flag(value)?,
+		_ => bail!("Unknown setting {key}"),
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn run_mock(server: &mock::MockServer, command: &[&str]) -> Result<String> {
+		let path = std::env::temp_dir().join(format!(
+			"hax-6rr-{}-{}-cli.ini",
+			std::process::id(),
+			command.join("-")
+		));
+		tokio::fs::write(
+			&path,
+			format!(
+				"[card]\naccessCode={}\nchipId={}\n",
+				mock::ACCESS_CODE,
+				mock::CHIP_ID
+			),
+		)
+		.await?;
+		let command = command
+			.iter()
+			.map(|arg| arg.to_string())
+			.collect::<Vec<_>>();
+		let output = execute(&server.client(), path.to_str().unwrap(), &command).await;
+		_ = tokio::fs::remove_file(&path).await;
+		output
+	}
+
+	#[tokio::test]
+	async fn give_grants_missing_items() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+
+		run_mock(&server, &["give", "100", "bgm", "color"])
+			.await
+			.unwrap();
+
+		let state = server.state.lock().unwrap();
+		let owned_items = &state.car(mock::CAR_ID).unwrap().owned_items;
+		assert_eq!(
+			owned_items
+				.iter()
+				.filter(|item| item.category() == wm::ItemCategory::CatCustomColor)
+				.count(),
+			40
+		);
+		assert!(owned_items
+			.iter()
+			.any(|item| item.category() == wm::ItemCategory::CatBgm));
+	}
+
+	#[tokio::test]
+	async fn set_saves_settings() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+
+		run_mock(
+			&server,
+			&["set", "100", "bgm=3", "view=true", "title=Batch"],
+		)
+		.await
+		.unwrap();
+
+		let state = server.state.lock().unwrap();
+		let car = state.car(mock::CAR_ID).unwrap();
+		assert_eq!(car.setting.bgm, 3);
+		assert!(car.setting.view);
+		assert_eq!(car.car.title, "Batch");
+	}
+
+	#[tokio::test]
+	async fn rejects_cars_from_other_cards() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+
+		assert!(run_mock(&server, &["dump", "999"]).await.is_err());
+		assert!(run_mock(&server, &["set", "100", "unknown=1"])
+			.await
+			.is_err());
+	}
+}
@@ -0,0 +1,277 @@
+//! The user's garage: what state each car is in, their order, and retiring cars.
+use crate::*;
+use anyhow::Result;
+use eframe::egui;
+use num_traits::FromPrimitive;
+
+pub struct GarageMenu {
+	pub card: Card,
+	pub user_id: u32,
+	/// Car ids in the order "Save order" sends them.
+	pub order: Vec<u32>,
+	pub confirm_delete: Option<u32>,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::LoadUserResponse>>,
+}
+
+impl GarageMenu {
+	pub fn new(card: Card, user_id: u32, cars: &[wm::Car]) -> Self {
+		Self {
+			card,
+			user_id,
+			order: cars.iter().map(|car| car.car_id()).collect(),
+			confirm_delete: None,
+			error: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the user as the server has it after a change went through.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		cars: &[wm::Car],
+		car_states: &[wm::load_user_response::CarState],
+	) -> Option<wm::LoadUserResponse> {
+		let updated = match task::poll(&mut self.pending) {
+			Some(Ok(user)) => {
+				self.order = user.cars.iter().map(|car| car.car_id()).collect();
+				self.error = None;
+				Some(user)
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		let mut action = None;
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
+			if let Some(car_id) = self.confirm_delete {
+				let name = cars
+					.iter()
+					.find(|car| car.car_id() == car_id)
+					.map_or("", |car| car.name());
+				ui.heading(format!("Delete {name}?"));
+				ui.label("The server removes the car for good once it processes the request.");
+				ui.horizontal(|ui| {
+					if ui.button("Delete").clicked() {
+						action = Some(GarageAction::Delete(car_id, true));
+						self.confirm_delete = None;
+					}
+					if ui.button("Cancel").clicked() {
+						self.confirm_delete = None;
+					}
+				});
+				return;
+			}
+
+			ui.label("Drag cars to reorder them");
+			let mut moved = None;
+			for (i, car_id) in self.order.iter().enumerate() {
+				let Some(index) = cars.iter().position(|car| car.car_id() == *car_id) else {
+					continue;
+				};
+				let car = &cars[index];
+				let state = car_states.get(index);
+
+				let response = ui
+					.horizontal(|ui| {
+						let id = egui::Id::new(("GarageCar", car_id));
+						ui.dnd_drag_source(id, i, |ui| {
+							ui.label(format!(
+								"{} ({})",
+								car.name(),
+								wm::Cars::from_u32(car.visual_model())
+									.map_or(String::new(), |model| model.to_string())
+							));
+						});
+
+						let to_be_deleted = state.is_some_and(|state| state.to_be_deleted);
+						if let Some(state) = state {
+							for flag in car_state_flags(state) {
+								ui.colored_label(egui::Color32::YELLOW, flag);
+							}
+						}
+						if to_be_deleted {
+							if ui.small_button("Keep").clicked() {
+								action = Some(GarageAction::Delete(*car_id, false));
+							}
+						} else if ui.small_button("Delete").clicked() {
+							self.confirm_delete = Some(*car_id);
+						}
+					})
+					.response;
+				if let Some(from) = response.dnd_release_payload::<usize>() {
+					moved = Some((*from, i));
+				}
+			}
+			if let Some((from, to)) = moved {
+				move_car(&mut self.order, from, to);
+			}
+
+			let original = cars.iter().map(|car| car.car_id()).collect::<Vec<_>>();
+			ui.horizontal(|ui| {
+				if ui
+					.add_enabled(self.order != original, egui::Button::new("Save order"))
+					.clicked()
+				{
+					action = Some(GarageAction::SaveOrder(self.order.clone()));
+				}
+				if ui
+					.add_enabled(self.order != original, egui::Button::new("Reset"))
+					.clicked()
+				{
+					self.order = original;
+				}
+			});
+		});
+
+		if let Some(action) = action {
+			let client = client.clone();
+			let card = self.card.clone();
+			let user_id = self.user_id;
+			self.error = None;
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				match action {
+					GarageAction::Delete(car_id, to_be_deleted) => {
+						set_to_be_deleted(&client, car_id, to_be_deleted).await?
+					}
+					GarageAction::SaveOrder(order) => {
+						save_car_order(&client, user_id, order).await?
+					}
+				}
+				load_user_with_card(&card, &client).await
+			}));
+		}
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
+
+		updated
+	}
+
+	pub fn back(&mut self) -> bool {
+		if self.confirm_delete.is_some() {
+			self.confirm_delete = None;
+			false
+		} else {
+			true
+		}
+	}
+}
+
+enum GarageAction {
+	Delete(u32, bool),
+	SaveOrder(Vec<u32>),
+}
+
+fn car_state_flags(state: &wm::load_user_response::CarState) -> Vec<&'static str> {
+	[
+		(state.to_be_deleted, "To be deleted"),
+		(state.transferred, "Transferred"),
+		(state.event_joined, "Event joined"),
+	]
+	.into_iter()
+	.filter_map(|(set, flag)| set.then_some(flag))
+	.collect()
+}
+
+/// Moves the car at `from` into position `to`, shifting the ones in between.
+fn move_car(order: &mut Vec<u32>, from: usize, to: usize) {
+	if from >= order.len() || to >= order.len() {
+		return;
+	}
+	let car_id = order.remove(from);
+	order.insert(to, car_id);
+}
+
+async fn set_to_be_deleted(
+	client: &client::WmClient,
+	car_id: u32,
+	to_be_deleted: bool,
+) -> Result<()> {
+	let req = wm::UpdateCarRequest {
+		car_id,
+		to_be_deleted: Some(to_be_deleted),
+		timestamp: timestamp(),
+		..Default::default()
+	};
+	client.update_car(req).await?;
+
+	Ok(())
+}
+
+async fn save_car_order(
+	client: &client::WmClient,
+	user_id: u32,
+	car_order: Vec<u32>,
+) -> Result<()> {
+	let req = wm::SaveTerminalResultRequest {
+		user_id,
+		timestamp: timestamp(),
+		car_order,
+		garage_menu_entered: Some(true),
+		..Default::default()
+	};
+	client.save_terminal_result(req).await?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn move_car_shifts_the_rest() {
+		let mut order = vec![1, 2, 3, 4];
+		move_car(&mut order, 0, 2);
+		assert_eq!(order, [2, 3, 1, 4]);
+		move_car(&mut order, 3, 0);
+		assert_eq!(order, [4, 2, 3, 1]);
+		move_car(&mut order, 1, 9);
+		assert_eq!(order, [4, 2, 3, 1]);
+	}
+
+	#[tokio::test]
+	async fn delete_and_reorder_show_up_on_reload() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let card = Card {
+			access_code: String::from(mock::ACCESS_CODE),
+			chip_id: String::from(mock::CHIP_ID),
+			..Default::default()
+		};
+		{
+			let mut state = server.state.lock().unwrap();
+			let mut car = state.cars[0].clone();
+			car.car.car_id = Some(mock::CAR_ID + 1);
+			state.cars.push(car);
+		}
+
+		set_to_be_deleted(&client, mock::CAR_ID, true)
+			.await
+			.unwrap();
+		save_car_order(&client, mock::USER_ID, vec![mock::CAR_ID + 1, mock::CAR_ID])
+			.await
+			.unwrap();
+
+		let user = load_user_with_card(&card, &client).await.unwrap();
+		let order = user.cars.iter().map(|car| car.car_id()).collect::<Vec<_>>();
+		assert_eq!(order, [mock::CAR_ID + 1, mock::CAR_ID]);
+		assert!(!user.car_states[0].to_be_deleted);
+		assert_eq!(car_state_flags(&user.car_states[1]), ["To be deleted"]);
+
+		set_to_be_deleted(&client, mock::CAR_ID, false)
+			.await
+			.unwrap();
+		let user = load_user_with_card(&card, &client).await.unwrap();
+		assert!(user.car_states.iter().all(|state| !state.to_be_deleted));
+	}
+}
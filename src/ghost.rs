@@ -0,0 +1,263 @@
+//! Ghost battle (RG) progress: play counts, trophies, stamps and the region map.
+use crate::*;
+use anyhow::Result;
+use eframe::egui;
+use num_traits::FromPrimitive;
+
+/// Regions on the map, `rg_region_map_score` is indexed by region id minus one.
+pub const REGIONS: u32 = 47;
+
+/// The ghost battle fields of a car that `GhostBattleResult` can write back.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GhostBattleProgress {
+	pub play_count: u32,
+	pub trophy: u32,
+	pub stamp: u32,
+	pub acquire_all_crowns: bool,
+	pub dressup_level: u32,
+	pub dressup_point: u32,
+	/// Padded out to `REGIONS` entries.
+	pub region_map_score: Vec<u32>,
+}
+
+impl GhostBattleProgress {
+	pub fn new(car: &wm::LoadCarResponse) -> Self {
+		let mut region_map_score = car.rg_region_map_score.clone();
+		if region_map_score.len() < REGIONS as usize {
+			region_map_score.resize(REGIONS as usize, 0);
+		}
+
+		Self {
+			play_count: car.rg_play_count,
+			trophy: car.rg_trophy,
+			stamp: car.rg_stamp,
+			acquire_all_crowns: car.rg_acquire_all_crowns,
+			dressup_level: car.dressup_level,
+			dressup_point: car.dressup_point,
+			region_map_score,
+		}
+	}
+
+	pub fn result(&self) -> wm::save_game_result_request::GhostBattleResult {
+		wm::save_game_result_request::GhostBattleResult {
+			rg_trophy: Some(self.trophy),
+			rg_stamp: Some(self.stamp),
+			acquire_all_crowns: Some(self.acquire_all_crowns),
+			dressup_level: Some(self.dressup_level),
+			dressup_point: Some(self.dressup_point),
+			rg_region_map_score: self.region_map_score.clone(),
+			..blank_result(self.play_count)
+		}
+	}
+}
+
+/// A battle against nobody on no particular path, for results that only carry progress.
+pub fn blank_result(rg_play_count: u32) -> wm::save_game_result_request::GhostBattleResult {
+	wm::save_game_result_request::GhostBattleResult {
+		path: 0,
+		is_morning: false,
+		selection_method: wm::GhostSelectionMethod::GhostDefaultOpponent.into(),
+		rg_play_count,
+		..Default::default()
+	}
+}
+
+pub struct GhostBattleMenu {
+	pub progress: GhostBattleProgress,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::LoadCarResponse>>,
+}
+
+impl GhostBattleMenu {
+	pub fn new(loaded: &wm::LoadCarResponse) -> Self {
+		Self {
+			progress: GhostBattleProgress::new(loaded),
+			error: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the car as the server has it after the progress was saved.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		loaded: &wm::LoadCarResponse,
+	) -> Option<wm::LoadCarResponse> {
+		let saved = match task::poll(&mut self.pending) {
+			Some(Ok(car)) => {
+				self.progress = GhostBattleProgress::new(&car);
+				self.error = None;
+				Some(car)
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		let unchanged = self.progress == GhostBattleProgress::new(loaded);
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
+			ui.horizontal(|ui| {
+				if ui
+					.add_enabled(!unchanged, egui::Button::new("Save"))
+					.clicked()
+				{
+					let client = client.clone();
+					let loaded = loaded.clone();
+					let progress = self.progress.clone();
+					self.error = None;
+					self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+						save_ghost_battle(&client, &loaded, progress.result()).await?;
+						load_car(loaded.car.car_id(), &client).await
+					}));
+				}
+				if ui
+					.add_enabled(!unchanged, egui::Button::new("Revert"))
+					.clicked()
+				{
+					self.progress = GhostBattleProgress::new(loaded);
+				}
+				if self.pending.is_some() {
+					ui.spinner();
+				}
+			});
+			show_error(ui, &self.error);
+
+			egui::Grid::new("GhostBattleGrid")
+				.num_columns(2)
+				.show(ui, |ui| {
+					ui.label("Play Count");
+					ui.add(egui::DragValue::new(&mut self.progress.play_count));
+					ui.end_row();
+
+					ui.label("Trophies");
+					ui.add(egui::DragValue::new(&mut self.progress.trophy));
+					ui.end_row();
+
+					ui.label("Stamps");
+					ui.add(egui::DragValue::new(&mut self.progress.stamp));
+					ui.end_row();
+
+					ui.label("Dressup Level");
+					ui.add(egui::DragValue::new(&mut self.progress.dressup_level));
+					ui.end_row();
+
+					ui.label("Dressup Points");
+					ui.add(egui::DragValue::new(&mut self.progress.dressup_point));
+					ui.end_row();
+
+					ui.label("Acquired All Crowns");
+					ui.add(egui::Checkbox::without_text(
+						&mut self.progress.acquire_all_crowns,
+					));
+					ui.end_row();
+
+					// GhostBattleResult has no fields for these, so they can only be shown
+					ui.label("Wins");
+					ui.label(loaded.rg_win_count.to_string());
+					ui.end_row();
+
+					ui.label("Score");
+					ui.label(loaded.rg_score.to_string());
+					ui.end_row();
+
+					ui.label("Score (VS 2)");
+					ui.label(loaded.rg_score_vs_2.to_string());
+					ui.end_row();
+				});
+
+			ui.separator();
+			ui.heading("Regions");
+			show_numbered(ui, "RgRegionsGrid", &loaded.rg_regions);
+
+			ui.separator();
+			ui.heading("Region Map Score");
+			let glb = loaded.car.country() == "GLB";
+			egui::Grid::new("RegionMapScoreGrid")
+				.num_columns(2)
+				.show(ui, |ui| {
+					for (region_id, score) in
+						(1..=REGIONS).zip(self.progress.region_map_score.iter_mut())
+					{
+						ui.label(region_name(region_id, glb));
+						ui.add(egui::DragValue::new(score));
+						ui.end_row();
+					}
+				});
+		});
+
+		saved
+	}
+
+	pub fn back(&mut self) -> bool {
+		true
+	}
+}
+
+pub fn region_name(region_id: u32, glb: bool) -> String {
+	let name = if glb {
+		wm::Glb::from_u32(region_id).map(|region| region.to_string())
+	} else {
+		wm::Jpn::from_u32(region_id).map(|region| region.to_string())
+	};
+	name.unwrap_or_else(|| region_id.to_string())
+}
+
+/// Sends the car as loaded so unsaved edits from the other menus don't go along.
+pub async fn save_ghost_battle(
+	client: &client::WmClient,
+	loaded: &wm::LoadCarResponse,
+	rg_result: wm::save_game_result_request::GhostBattleResult,
+) -> Result<wm::SaveGameResultResponse> {
+	let req = wm::SaveGameResultRequest {
+		car_id: loaded.car.car_id(),
+		game_mode: wm::GameMode::ModeGhostBattle.into(),
+		played_at: loaded.car.last_played_at(),
+		play_count: loaded.play_count,
+		car: Some(loaded.car.clone()),
+		setting: Some(loaded.setting.clone()),
+		odometer: Some(loaded.odometer),
+		earned_custom_color: Some(loaded.earned_custom_color),
+		retired: false,
+		rg_result: Some(rg_result),
+		..Default::default()
+	};
+
+	client.save_game_result(req).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn save_ghost_battle_writes_progress() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let loaded = load_car(mock::CAR_ID, &client).await.unwrap();
+		let mut progress = GhostBattleProgress::new(&loaded);
+		assert_eq!(progress.region_map_score.len(), REGIONS as usize);
+		progress.play_count = 50;
+		progress.trophy = 3;
+		progress.acquire_all_crowns = true;
+		progress.region_map_score[12] = 900;
+
+		save_ghost_battle(&client, &loaded, progress.result())
+			.await
+			.unwrap();
+
+		let car = load_car(mock::CAR_ID, &client).await.unwrap();
+		assert_eq!(GhostBattleProgress::new(&car), progress);
+		assert_eq!(car.rg_region_map_score[12], 900);
+	}
+
+	#[test]
+	fn region_names_follow_the_country() {
+		assert_eq!(region_name(13, false), "東京");
+		assert_eq!(region_name(13, true), "FRA");
+		assert_eq!(region_name(999, false), "999");
+	}
+}
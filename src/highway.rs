@@ -0,0 +1,225 @@
+//! Ghost Highway progress: the station board, points and the last dice roll.
+use crate::*;
+use eframe::egui;
+
+/// Stations tracked by `rg_highway_station_clear_bits`, one bit each.
+pub const STATIONS: usize = 32;
+const STATIONS_PER_ROW: usize = 8;
+const MAX_DICE: u32 = 6;
+
+/// The Ghost Highway fields of a car, as `GhostHighwayResult` writes them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighwayProgress {
+	pub clear_count: u32,
+	pub point: u32,
+	pub station_clear_bits: u32,
+	pub previous_dice: u32,
+}
+
+impl HighwayProgress {
+	pub fn new(car: &wm::LoadCarResponse) -> Self {
+		Self {
+			clear_count: car.rg_highway_clear_count,
+			point: car.rg_highway_point,
+			station_clear_bits: car.rg_highway_station_clear_bits,
+			previous_dice: car.rg_highway_previous_dice,
+		}
+	}
+
+	pub fn station_cleared(&self, station: usize) -> bool {
+		self.station_clear_bits & (1 << station) != 0
+	}
+
+	pub fn toggle_station(&mut self, station: usize) {
+		self.station_clear_bits ^= 1 << station;
+	}
+
+	pub fn result(
+		&self,
+		region_id: u32,
+	) -> wm::save_game_result_request::ghost_battle_result::GhostHighwayResult {
+		wm::save_game_result_request::ghost_battle_result::GhostHighwayResult {
+			rg_highway_clear_count: self.clear_count,
+			rg_highway_point: self.point,
+			rg_highway_station_clear_bits: self.station_clear_bits,
+			rg_highway_previous_dice: self.previous_dice,
+			rg_highway_region_id: region_id,
+		}
+	}
+}
+
+pub struct HighwayMenu {
+	pub progress: HighwayProgress,
+	/// Where the result says the highway was played, the car's own region unless picked.
+	pub region_id: u32,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::LoadCarResponse>>,
+}
+
+impl HighwayMenu {
+	pub fn new(loaded: &wm::LoadCarResponse) -> Self {
+		Self {
+			progress: HighwayProgress::new(loaded),
+			region_id: loaded.car.region_id(),
+			error: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the car as the server has it after the progress was saved.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		loaded: &wm::LoadCarResponse,
+	) -> Option<wm::LoadCarResponse> {
+		let saved = match task::poll(&mut self.pending) {
+			Some(Ok(car)) => {
+				self.progress = HighwayProgress::new(&car);
+				self.error = None;
+				Some(car)
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		let unchanged = self.progress == HighwayProgress::new(loaded)
+			&& self.region_id == loaded.car.region_id();
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
+			ui.horizontal(|ui| {
+				if ui
+					.add_enabled(!unchanged, egui::Button::new("Save"))
+					.clicked()
+				{
+					let client = client.clone();
+					let loaded = loaded.clone();
+					let rg_result = highway_result(&loaded, &self.progress, self.region_id);
+					self.error = None;
+					self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+						ghost::save_ghost_battle(&client, &loaded, rg_result).await?;
+						load_car(loaded.car.car_id(), &client).await
+					}));
+				}
+				if ui
+					.add_enabled(!unchanged, egui::Button::new("Revert"))
+					.clicked()
+				{
+					self.progress = HighwayProgress::new(loaded);
+					self.region_id = loaded.car.region_id();
+				}
+				if self.pending.is_some() {
+					ui.spinner();
+				}
+			});
+			show_error(ui, &self.error);
+
+			ui.label("Click a station to toggle whether it's cleared");
+			egui::Grid::new("HighwayStationGrid")
+				.num_columns(STATIONS_PER_ROW)
+				.show(ui, |ui| {
+					for station in 0..STATIONS {
+						let text = egui::RichText::new(format!("{}", station + 1));
+						let text = if self.progress.station_cleared(station) {
+							text.color(egui::Color32::GREEN)
+						} else {
+							text
+						};
+						if ui.button(text).clicked() {
+							self.progress.toggle_station(station);
+						}
+						if station % STATIONS_PER_ROW == STATIONS_PER_ROW - 1 {
+							ui.end_row();
+						}
+					}
+				});
+
+			egui::Grid::new("HighwayGrid")
+				.num_columns(2)
+				.show(ui, |ui| {
+					ui.label("Clear Count");
+					ui.add(egui::DragValue::new(&mut self.progress.clear_count));
+					ui.end_row();
+
+					ui.label("Points");
+					ui.add(egui::DragValue::new(&mut self.progress.point));
+					ui.end_row();
+
+					ui.label("Previous Dice");
+					ui.add(egui::Slider::new(
+						&mut self.progress.previous_dice,
+						0..=MAX_DICE,
+					));
+					ui.end_row();
+
+					let glb = car::is_glb(&loaded.car);
+					ui.label("Played In");
+					egui::ComboBox::from_id_source("HighwayRegionComboBox")
+						.selected_text(ghost::region_name(self.region_id, glb))
+						.show_ui(ui, |ui| {
+							for region_id in 1..=ghost::REGIONS {
+								ui.selectable_value(
+									&mut self.region_id,
+									region_id,
+									ghost::region_name(region_id, glb),
+								);
+							}
+						});
+					ui.end_row();
+				});
+		});
+
+		saved
+	}
+
+	pub fn back(&mut self) -> bool {
+		true
+	}
+}
+
+fn highway_result(
+	loaded: &wm::LoadCarResponse,
+	progress: &HighwayProgress,
+	region_id: u32,
+) -> wm::save_game_result_request::GhostBattleResult {
+	wm::save_game_result_request::GhostBattleResult {
+		highway_result: Some(progress.result(region_id)),
+		..ghost::blank_result(loaded.rg_play_count)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn save_highway_writes_progress() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let loaded = load_car(mock::CAR_ID, &client).await.unwrap();
+		let mut progress = HighwayProgress::new(&loaded);
+		progress.toggle_station(0);
+		progress.toggle_station(5);
+		progress.toggle_station(0);
+		progress.point = 120;
+		progress.previous_dice = 4;
+		assert!(progress.station_cleared(5));
+		assert_eq!(progress.station_clear_bits, 1 << 5);
+
+		let rg_result = highway_result(&loaded, &progress, 5);
+		assert_eq!(
+			rg_result.highway_result.as_ref().unwrap().rg_highway_region_id,
+			5
+		);
+		ghost::save_ghost_battle(&client, &loaded, rg_result)
+			.await
+			.unwrap();
+
+		let car = load_car(mock::CAR_ID, &client).await.unwrap();
+		assert_eq!(HighwayProgress::new(&car), progress);
+		assert_eq!(car.rg_play_count, loaded.rg_play_count);
+	}
+}
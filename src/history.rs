@@ -0,0 +1,382 @@
+//! A car's game history: time attack rankings, recent ghost battles and the stamp sheet.
+use crate::*;
+use anyhow::Result;
+use eframe::egui;
+use num_traits::FromPrimitive;
+
+type TaRecord = wm::load_game_history_response::TimeAttackRecord;
+type GhostRecord = wm::load_game_history_response::GhostBattleRecord;
+type RecordCar = wm::load_game_history_response::ghost_battle_record::GhostBattleRecordCar;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaColumn {
+	Course,
+	Time,
+	WholeRank,
+	ModelRank,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GhostColumn {
+	PlayedAt,
+	Opponent,
+	Result,
+	Area,
+	Shop,
+}
+
+/// Which column a table is sorted by, clicking its header again flips the order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sort<C> {
+	pub column: C,
+	pub descending: bool,
+}
+
+impl<C: Copy + PartialEq> Sort<C> {
+	fn header(&mut self, ui: &mut egui::Ui, column: C, label: &str) {
+		let text = if self.column != column {
+			label.to_string()
+		} else if self.descending {
+			format!("{label} ⏷")
+		} else {
+			format!("{label} ⏶")
+		};
+		if ui.button(text).clicked() {
+			if self.column == column {
+				self.descending = !self.descending;
+			} else {
+				self.column = column;
+				self.descending = false;
+			}
+		}
+	}
+
+	fn apply<T, K: Ord>(&self, rows: &mut [T], key: impl Fn(&T) -> K) {
+		rows.sort_by_key(|row| key(row));
+		if self.descending {
+			rows.reverse();
+		}
+	}
+}
+
+pub struct HistoryMenu {
+	pub history: Option<wm::LoadGameHistoryResponse>,
+	pub ta_sort: Sort<TaColumn>,
+	pub ghost_sort: Sort<GhostColumn>,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::LoadGameHistoryResponse>>,
+}
+
+impl Default for HistoryMenu {
+	fn default() -> Self {
+		Self {
+			history: None,
+			ta_sort: Sort {
+				column: TaColumn::Course,
+				descending: false,
+			},
+			ghost_sort: Sort {
+				column: GhostColumn::PlayedAt,
+				descending: true,
+			},
+			error: None,
+			pending: None,
+		}
+	}
+}
+
+impl HistoryMenu {
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		loaded: &wm::LoadCarResponse,
+	) {
+		match task::poll(&mut self.pending) {
+			Some(Ok(history)) => {
+				self.history = Some(history);
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+
+		// Loaded as soon as the menu opens, afterwards only when asked to
+		let reload = ui
+			.add_enabled(self.pending.is_none(), egui::Button::new("Reload"))
+			.clicked();
+		if reload || (self.history.is_none() && self.pending.is_none() && self.error.is_none()) {
+			let client = client.clone();
+			let car_id = loaded.car.car_id();
+			self.error = None;
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				load_game_history(&client, car_id).await
+			}));
+		}
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
+
+		let Some(history) = &self.history else {
+			return;
+		};
+
+		egui::ScrollArea::vertical().show(ui, |ui| {
+			egui::Grid::new("HistorySummaryGrid")
+				.num_columns(2)
+				.show(ui, |ui| {
+					ui.label("Ghost Battles");
+					ui.label(history.ghost_battle_count.to_string());
+					ui.end_row();
+
+					ui.label("Wins");
+					ui.label(history.ghost_battle_win_count.to_string());
+					ui.end_row();
+
+					ui.label("Losses");
+					ui.label(
+						history
+							.ghost_battle_count
+							.saturating_sub(history.ghost_battle_win_count)
+							.to_string(),
+					);
+					ui.end_row();
+
+					ui.label("Stamp Sheets");
+					ui.label(history.stamp_sheet_count.to_string());
+					ui.end_row();
+				});
+
+			ui.separator();
+			ui.heading("Stamp Sheet");
+			show_numbered(ui, "HistoryStampSheetGrid", &history.stamp_sheet);
+
+			ui.separator();
+			ui.heading("Time Attack");
+			ta_table(ui, &mut self.ta_sort, history);
+
+			ui.separator();
+			ui.heading("Ghost Battles");
+			ghost_table(ui, &mut self.ghost_sort, history);
+		});
+	}
+
+	pub fn back(&mut self) -> bool {
+		true
+	}
+}
+
+fn ta_table(ui: &mut egui::Ui, sort: &mut Sort<TaColumn>, history: &wm::LoadGameHistoryResponse) {
+	egui::Grid::new("HistoryTaGrid")
+		.num_columns(5)
+		.striped(true)
+		.show(ui, |ui| {
+			sort.header(ui, TaColumn::Course, "Course");
+			sort.header(ui, TaColumn::Time, "Time");
+			ui.label("Tune");
+			sort.header(ui, TaColumn::WholeRank, "Rank");
+			sort.header(ui, TaColumn::ModelRank, "Model Rank");
+			ui.end_row();
+
+			for record in sorted_ta_records(&history.ta_records, *sort) {
+				ui.label(record.course.to_string());
+				ui.label(
+					record
+						.time
+						.map_or(String::from("-"), timeattack::format_time),
+				);
+				ui.label(format!(
+					"{}/{}",
+					record.tune_power(),
+					record.tune_handling()
+				));
+				ui.label(rank(record.whole_rank, record.whole_participants));
+				ui.label(rank(record.model_rank, record.model_participants));
+				ui.end_row();
+			}
+		});
+}
+
+fn ghost_table(
+	ui: &mut egui::Ui,
+	sort: &mut Sort<GhostColumn>,
+	history: &wm::LoadGameHistoryResponse,
+) {
+	egui::Grid::new("HistoryGhostGrid")
+		.num_columns(7)
+		.striped(true)
+		.show(ui, |ui| {
+			sort.header(ui, GhostColumn::PlayedAt, "Played At");
+			sort.header(ui, GhostColumn::Opponent, "Opponent");
+			ui.label("Region");
+			sort.header(ui, GhostColumn::Result, "Result");
+			sort.header(ui, GhostColumn::Area, "Area");
+			sort.header(ui, GhostColumn::Shop, "Shop");
+			ui.label("Mobs");
+			ui.end_row();
+
+			for record in sorted_ghost_history(&history.ghost_history, *sort) {
+				ui.label(record.played_at.to_string());
+				ui.label(record_car_name(&record.opponent));
+				ui.label(region_name(&record.opponent));
+				ui.label(record.opponent.result.to_string());
+				ui.label(record.area.to_string());
+				ui.label(&record.played_shop_name);
+				let mobs = record.mobs.iter().map(record_car_name).collect::<Vec<_>>();
+				ui.label(mobs.len().to_string())
+					.on_hover_text(mobs.join("\n"));
+				ui.end_row();
+			}
+		});
+}
+
+/// Named from the opponent's own country, since GLB and JPN cars can meet.
+fn region_name(record: &RecordCar) -> String {
+	ghost::region_name(record.car.region_id(), car::is_glb(&record.car))
+}
+
+/// Records without a time or rank count as the slowest, lowest ranked ones.
+fn sorted_ta_records(records: &[TaRecord], sort: Sort<TaColumn>) -> Vec<&TaRecord> {
+	let mut records = records.iter().collect::<Vec<_>>();
+	match sort.column {
+		TaColumn::Course => sort.apply(&mut records, |record| record.course),
+		TaColumn::Time => sort.apply(&mut records, |record| (record.time.is_none(), record.time)),
+		TaColumn::WholeRank => sort.apply(&mut records, |record| {
+			(record.whole_rank.is_none(), record.whole_rank)
+		}),
+		TaColumn::ModelRank => sort.apply(&mut records, |record| {
+			(record.model_rank.is_none(), record.model_rank)
+		}),
+	}
+	records
+}
+
+fn sorted_ghost_history(records: &[GhostRecord], sort: Sort<GhostColumn>) -> Vec<&GhostRecord> {
+	let mut records = records.iter().collect::<Vec<_>>();
+	match sort.column {
+		GhostColumn::PlayedAt => sort.apply(&mut records, |record| record.played_at),
+		GhostColumn::Opponent => {
+			sort.apply(&mut records, |record| record_car_name(&record.opponent))
+		}
+		GhostColumn::Result => sort.apply(&mut records, |record| record.opponent.result),
+		GhostColumn::Area => sort.apply(&mut records, |record| record.area),
+		GhostColumn::Shop => sort.apply(&mut records, |record| record.played_shop_name.clone()),
+	}
+	records
+}
+
+fn record_car_name(record: &RecordCar) -> String {
+	let model = wm::Cars::from_u32(record.car.visual_model())
+		.map_or(String::new(), |model| model.to_string());
+	format!("{} ({model})", record.car.name())
+}
+
+fn rank(rank: Option<u32>, participants: u32) -> String {
+	match rank {
+		Some(rank) => format!("{rank} / {participants}"),
+		None => format!("- / {participants}"),
+	}
+}
+
+async fn load_game_history(
+	client: &client::WmClient,
+	car_id: u32,
+) -> Result<wm::LoadGameHistoryResponse> {
+	let req = wm::LoadGameHistoryRequest { car_id };
+	client.load_game_history(req).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn opponent(name: &str, result: i32) -> RecordCar {
+		RecordCar {
+			car: wm::Car {
+				name: Some(String::from(name)),
+				visual_model: Some(wm::Cars::FD3S as u32),
+				region_id: Some(13),
+				..Default::default()
+			},
+			result,
+		}
+	}
+
+	#[tokio::test]
+	async fn load_game_history_sorts_by_column() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		server.state.lock().unwrap().game_histories.insert(
+			mock::CAR_ID,
+			wm::LoadGameHistoryResponse {
+				ta_records: vec![
+					TaRecord {
+						course: 2,
+						time: Some(125_123),
+						whole_rank: Some(10),
+						..Default::default()
+					},
+					TaRecord {
+						course: 1,
+						time: Some(98_000),
+						whole_rank: Some(3),
+						..Default::default()
+					},
+				],
+				ghost_history: vec![
+					GhostRecord {
+						opponent: opponent("B", -100),
+						played_at: 20,
+						..Default::default()
+					},
+					GhostRecord {
+						opponent: opponent("A", 300),
+						played_at: 10,
+						..Default::default()
+					},
+				],
+				ghost_battle_count: 2,
+				ghost_battle_win_count: 1,
+				..Default::default()
+			},
+		);
+
+		let history = load_game_history(&client, mock::CAR_ID).await.unwrap();
+		assert_eq!(history.ghost_battle_win_count, 1);
+		assert!(load_game_history(&client, mock::CAR_ID + 1).await.is_err());
+
+		let sort = Sort {
+			column: TaColumn::WholeRank,
+			descending: false,
+		};
+		let courses = sorted_ta_records(&history.ta_records, sort)
+			.iter()
+			.map(|record| record.course)
+			.collect::<Vec<_>>();
+		assert_eq!(courses, [1, 2]);
+
+		let sort = Sort {
+			column: GhostColumn::Result,
+			descending: true,
+		};
+		let names = sorted_ghost_history(&history.ghost_history, sort)
+			.iter()
+			.map(|record| record_car_name(&record.opponent))
+			.collect::<Vec<_>>();
+		assert_eq!(names[0], record_car_name(&opponent("A", 0)));
+		assert!(names[1].starts_with("B ("));
+	}
+
+	#[test]
+	fn opponent_regions_follow_their_own_country() {
+		let jpn = opponent("A", 0);
+		let mut glb = opponent("B", 0);
+		glb.car.country = Some(String::from("GLB"));
+
+		assert_eq!(region_name(&jpn), "東京");
+		assert_eq!(region_name(&glb), "FRA");
+	}
+}
@@ -0,0 +1,321 @@
+//! Items waiting on the user to be received, and handing them to one of their cars.
+use crate::*;
+use anyhow::Result;
+use eframe::egui;
+use std::collections::HashMap;
+
+/// A pending item and the cars the server says can take it.
+pub struct InboxEntry {
+	pub item: wm::UserItem,
+	pub car_ids: Vec<u32>,
+}
+
+pub struct InboxMenu {
+	pub card: Card,
+	pub user_id: u32,
+	pub entries: Option<Vec<InboxEntry>>,
+	/// The car picked for each entry, by user item id.
+	pub targets: HashMap<u32, u32>,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<(Vec<InboxEntry>, Option<wm::LoadUserResponse>)>>,
+}
+
+impl InboxMenu {
+	pub fn new(card: Card, user_id: u32) -> Self {
+		Self {
+			card,
+			user_id,
+			entries: None,
+			targets: HashMap::new(),
+			error: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the reloaded user after an item was received.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		cars: &[wm::Car],
+	) -> Option<wm::LoadUserResponse> {
+		let received = match task::poll(&mut self.pending) {
+			Some(Ok((entries, user))) => {
+				self.targets.retain(|id, _| {
+					entries
+						.iter()
+						.any(|entry| entry.item.user_item_id == Some(*id))
+				});
+				self.entries = Some(entries);
+				self.error = None;
+				user
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		let mut receive = None;
+		// Loaded as soon as the menu opens, afterwards only when asked to
+		let reload = ui
+			.add_enabled(self.pending.is_none(), egui::Button::new("Reload"))
+			.clicked();
+
+		if let Some(entries) = &self.entries {
+			if entries.is_empty() {
+				ui.label("Nothing waiting to be received");
+			}
+			ui.add_enabled_ui(self.pending.is_none(), |ui| {
+				egui::Grid::new("InboxGrid")
+					.num_columns(5)
+					.striped(true)
+					.show(ui, |ui| {
+						ui.label("Item");
+						ui.label("Earned");
+						ui.label("Expires");
+						ui.label("Car");
+						ui.label("Receive");
+						ui.end_row();
+
+						for entry in entries {
+							let Some(user_item_id) = entry.item.user_item_id else {
+								continue;
+							};
+							ui.label(item_label(&entry.item));
+							ui.label(entry.item.earned_at.map_or(String::from("-"), format_date));
+							ui.label(
+								entry
+									.item
+									.expire_at
+									.map_or(String::from("Never"), format_date),
+							);
+
+							if entry.car_ids.is_empty() {
+								ui.label("No car can receive this");
+								ui.end_row();
+								continue;
+							}
+							let target =
+								self.targets.entry(user_item_id).or_insert(entry.car_ids[0]);
+							egui::ComboBox::from_id_source(("InboxCarComboBox", user_item_id))
+								.selected_text(car_name(cars, *target))
+								.show_ui(ui, |ui| {
+									for car_id in entry.car_ids.iter() {
+										ui.selectable_value(
+											target,
+											*car_id,
+											car_name(cars, *car_id),
+										);
+									}
+								});
+							if ui.button("Receive").clicked() {
+								receive = Some((*target, user_item_id));
+							}
+							ui.end_row();
+						}
+					});
+			});
+		}
+
+		let load =
+			reload || (self.entries.is_none() && self.pending.is_none() && self.error.is_none());
+		if (load || receive.is_some()) && self.pending.is_none() {
+			let client = client.clone();
+			let card = self.card.clone();
+			let user_id = self.user_id;
+			self.error = None;
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				// The cars and car tickets the app shows only come back from the user load
+				let user = match receive {
+					Some((car_id, user_item_id)) => {
+						receive_user_items(&client, car_id, vec![user_item_id]).await?;
+						Some(load_user_with_card(&card, &client).await?)
+					}
+					None => None,
+				};
+				Ok((load_inbox(&client, user_id).await?, user))
+			}));
+		}
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
+
+		received
+	}
+
+	pub fn back(&mut self) -> bool {
+		true
+	}
+}
+
+fn item_label(item: &wm::UserItem) -> String {
+	if item.category() == wm::ItemCategory::CatCarTitle {
+		format!("Title: {}", item.title_name())
+	} else {
+		items::item_name(item.category(), item.item_id)
+	}
+}
+
+fn car_name(cars: &[wm::Car], car_id: u32) -> String {
+	match cars.iter().find(|car| car.car_id() == car_id) {
+		Some(car) => car.name().to_string(),
+		None => format!("Car {car_id}"),
+	}
+}
+
+/// The UTC date of a unix timestamp, as 2024-05-01.
+fn format_date(timestamp: u32) -> String {
+	// Days to civil date from Howard Hinnant's date algorithms
+	let days = timestamp as i64 / 86_400 + 719_468;
+	let era = days / 146_097;
+	let day_of_era = days - era * 146_097;
+	let year_of_era =
+		(day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month_index = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+	let month = if month_index < 10 {
+		month_index + 3
+	} else {
+		month_index - 9
+	};
+	let year = year_of_era + era * 400 + (month <= 2) as i64;
+	format!("{year}-{month:02}-{day:02}")
+}
+
+/// Every pending item with the cars that can take it.
+async fn load_inbox(client: &client::WmClient, user_id: u32) -> Result<Vec<InboxEntry>> {
+	let req = wm::LoadUnreceivedUserItemsRequest { user_id };
+	let user_items = client
+		.load_unreceived_user_items(req)
+		.await?
+		.owned_user_items;
+
+	let mut entries = Vec::with_capacity(user_items.len());
+	for item in user_items {
+		let req = wm::CheckItemReceivableCarsRequest {
+			user_id,
+			items_to_be_earned: vec![item.clone()],
+		};
+		let car_ids = client.check_item_receivable_cars(req).await?.car_ids;
+		entries.push(InboxEntry { item, car_ids });
+	}
+
+	Ok(entries)
+}
+
+async fn receive_user_items(
+	client: &client::WmClient,
+	car_id: u32,
+	target_item_ids: Vec<u32>,
+) -> Result<()> {
+	let req = wm::ReceiveUserItemsRequest {
+		car_id,
+		target_item_ids,
+	};
+	client.receive_user_items(req).await?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dates_are_utc() {
+		assert_eq!(format_date(0), "1970-01-01");
+		assert_eq!(format_date(951_782_400), "2000-02-29");
+		assert_eq!(format_date(1_714_521_600), "2024-05-01");
+	}
+
+	#[tokio::test]
+	async fn received_item_moves_to_the_car() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		server.state.lock().unwrap().users[0].unreceived_items = vec![
+			wm::UserItem {
+				category: wm::ItemCategory::CatBgm.into(),
+				item_id: 2,
+				user_item_id: Some(1),
+				..Default::default()
+			},
+			wm::UserItem {
+				category: wm::ItemCategory::CatCarTicketFree.into(),
+				item_id: 5,
+				user_item_id: Some(2),
+				..Default::default()
+			},
+		];
+
+		let entries = load_inbox(&client, mock::USER_ID).await.unwrap();
+		assert_eq!(entries[0].car_ids, [mock::CAR_ID]);
+		assert!(entries[1].car_ids.is_empty());
+		assert!(receive_user_items(&client, mock::CAR_ID, vec![2])
+			.await
+			.is_err());
+
+		receive_user_items(&client, mock::CAR_ID, vec![1])
+			.await
+			.unwrap();
+
+		let entries = load_inbox(&client, mock::USER_ID).await.unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].item.user_item_id, Some(2));
+		let car = load_car(mock::CAR_ID, &client).await.unwrap();
+		assert!(car.owned_items.contains(&wm::CarItem {
+			category: wm::ItemCategory::CatBgm.into(),
+			item_id: 2,
+			amount: Some(1),
+		}));
+	}
+
+	#[tokio::test]
+	async fn received_item_keeps_car_tickets_apart_from_the_inbox() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let ticket = wm::UserItem {
+			category: wm::ItemCategory::CatCarTicketFree.into(),
+			item_id: 5,
+			user_item_id: Some(1),
+			..Default::default()
+		};
+		let bgm = wm::UserItem {
+			category: wm::ItemCategory::CatBgm.into(),
+			item_id: 2,
+			user_item_id: Some(2),
+			..Default::default()
+		};
+		let title = wm::UserItem {
+			category: wm::ItemCategory::CatCarTitle.into(),
+			user_item_id: Some(3),
+			title_name: Some(String::from("TITLE")),
+			..Default::default()
+		};
+		{
+			let mut state = server.state.lock().unwrap();
+			state.users[0].car_tickets = vec![ticket.clone()];
+			state.users[0].unreceived_items = vec![bgm, title.clone()];
+		}
+		let card = Card {
+			access_code: String::from(mock::ACCESS_CODE),
+			chip_id: String::from(mock::CHIP_ID),
+			..Default::default()
+		};
+
+		receive_user_items(&client, mock::CAR_ID, vec![2])
+			.await
+			.unwrap();
+
+		let user = load_user_with_card(&card, &client).await.unwrap();
+		assert_eq!(user.unused_car_tickets, [ticket]);
+		let entries = load_inbox(&client, mock::USER_ID).await.unwrap();
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].item, title);
+	}
+}
@@ -3,7 +3,6 @@ use anyhow::Result;
 use eframe::egui;
 use num_traits::{FromPrimitive, ToPrimitive};
 use strum::IntoEnumIterator;
-use url::Url;
 
 fn show_enum_items<T>(car_items: &Vec<&wm::CarItem>, ui: &mut egui::Ui, new_item_buf: &mut String)
 where
@@ -48,26 +47,20 @@ where
 		});
 }
 
-async fn give_all_enum_items<T>(server: &Url, car: &wm::Car, car_items: &mut Vec<wm::CarItem>)
+fn missing_enum_items<T>(car_items: &[wm::CarItem]) -> Vec<wm::CarItem>
 where
 	T: IntoEnumIterator,
 	T: ToPrimitive,
 	T: wm::GetCategory,
 {
-	let mut items = T::iter()
+	T::iter()
 		.map(|id| wm::CarItem {
 			item_id: id.to_u32().unwrap(),
 			category: T::category().into(),
 			amount: Some(1),
 		})
 		.filter(|item| !car_items.contains(item))
-		.collect::<Vec<_>>();
-	if update_car_items(server, car.clone(), None, items.clone())
-		.await
-		.is_ok()
-	{
-		car_items.append(&mut items);
-	}
+		.collect()
 }
 
 fn show_vs_items(car_items: &Vec<&wm::CarItem>, ui: &mut egui::Ui, new_item_buf: &mut String) {
@@ -120,7 +113,7 @@ fn show_vs_items(car_items: &Vec<&wm::CarItem>, ui: &mut egui::Ui, new_item_buf:
 		});
 }
 
-async fn give_all_vs_items(server: &Url, car: &wm::Car, car_items: &mut Vec<wm::CarItem>) {
+fn missing_vs_items(car_items: &[wm::CarItem]) -> Vec<wm::CarItem> {
 	let mut items = vec![];
 	for grade in 1..=(wm::VS_GRADES.len() * 3) {
 		let item = wm::CarItem {
@@ -142,15 +135,10 @@ async fn give_all_vs_items(server: &Url, car: &wm::Car, car_items: &mut Vec<wm::
 			items.push(item);
 		}
 	}
-	if update_car_items(server, car.clone(), None, items.clone())
-		.await
-		.is_ok()
-	{
-		car_items.append(&mut items);
-	}
+	items
 }
 
-async fn give_all_colors(server: &Url, car: &wm::Car, car_items: &mut Vec<wm::CarItem>) {
+fn missing_colors(car_items: &[wm::CarItem]) -> Vec<wm::CarItem> {
 	let mut items = vec![];
 	for color in 1..=40 {
 		let item = wm::CarItem {
@@ -162,22 +150,78 @@ async fn give_all_colors(server: &Url, car: &wm::Car, car_items: &mut Vec<wm::Ca
 			items.push(item);
 		}
 	}
-	if update_car_items(server, car.clone(), None, items.clone())
-		.await
-		.is_ok()
-	{
-		car_items.append(&mut items);
+	items
+}
+
+fn missing_du_items(cars: &wm::Cars, car_items: &[wm::CarItem]) -> Vec<wm::CarItem> {
+	let mut items = vec![];
+	for item in wm::DU_ITEMS {
+		if cars.can_use_du_item(&item) {
+			let item = item.into();
+			if !car_items.contains(&item) {
+				items.push(item);
+			}
+		}
 	}
+	items
 }
 
-async fn show_dress_up_items(
-	car_items: &mut Vec<wm::CarItem>,
+fn enum_item_name<T>(item_id: u32) -> Option<String>
+where
+	T: FromPrimitive,
+	T: ToString,
+{
+	T::from_u32(item_id).map(|item| item.to_string())
+}
+
+/// A readable name for any item, falling back to the category and id for ones without a catalog.
+pub fn item_name(category: wm::ItemCategory, item_id: u32) -> String {
+	let name = match category {
+		wm::ItemCategory::CatBgm => enum_item_name::<wm::Bgms>(item_id),
+		wm::ItemCategory::CatMeter => enum_item_name::<wm::Meters>(item_id),
+		wm::ItemCategory::CatNamePlate => enum_item_name::<wm::Nameplates>(item_id),
+		wm::ItemCategory::CatRivalMarker => enum_item_name::<wm::RivalMarker>(item_id),
+		wm::ItemCategory::CatCustomFrame => enum_item_name::<wm::CustomFrame>(item_id),
+		wm::ItemCategory::CatWindowDecoration => enum_item_name::<wm::TeamDeco>(item_id),
+		wm::ItemCategory::CatTerminalBackground => {
+			enum_item_name::<wm::TerminalBackground>(item_id)
+		}
+		wm::ItemCategory::CatWheel => enum_item_name::<wm::Wheel>(item_id),
+		wm::ItemCategory::CatCustomColor => Some(format!("Custom Color {item_id}")),
+		wm::ItemCategory::CatCarTicketFree => newcar::ticket_name(item_id).map(String::from),
+		_ => wm::DU_ITEMS
+			.iter()
+			.find(|item| item.category == category && item.server_id == item_id)
+			.map(|item| String::from(item.name)),
+	};
+	name.unwrap_or_else(|| format!("{} #{item_id}", category.as_str_name()))
+}
+
+/// Every item of `category` the car can use but does not own yet.
+pub fn missing_items(
+	category: wm::ItemCategory,
+	cars: &wm::Cars,
+	car_items: &[wm::CarItem],
+) -> Vec<wm::CarItem> {
+	match category {
+		wm::ItemCategory::CatAuraMotif => missing_vs_items(car_items),
+		wm::ItemCategory::CatBgm => missing_enum_items::<wm::Bgms>(car_items),
+		wm::ItemCategory::CatMeter => missing_enum_items::<wm::Meters>(car_items),
+		wm::ItemCategory::CatNamePlate => missing_enum_items::<wm::Nameplates>(car_items),
+		wm::ItemCategory::CatRivalMarker => missing_enum_items::<wm::RivalMarker>(car_items),
+		wm::ItemCategory::CatCustomFrame => missing_enum_items::<wm::CustomFrame>(car_items),
+		wm::ItemCategory::CatCustomColor => missing_colors(car_items),
+		wm::ItemCategory::CatAero => missing_du_items(cars, car_items),
+		_ => vec![],
+	}
+}
+
+fn show_dress_up_items(
+	car_items: &[wm::CarItem],
 	ui: &mut egui::Ui,
 	new_item_buf: &mut String,
 	cars: &wm::Cars,
-	server: &Url,
-	car: &wm::Car,
-) {
+) -> Option<wm::CarItem> {
 	fn category_to_str(category: wm::ItemCategory) -> &'static str {
 		match category {
 			wm::ItemCategory::CatWheel => "Wheels",
@@ -243,32 +287,25 @@ async fn show_dress_up_items(
 		});
 
 	if ui.button("Add item").clicked() {
-		let item = wm::DU_ITEMS
+		wm::DU_ITEMS
 			.iter()
 			.find(|item| new_item_buf == item.name && cars.can_use_du_item(item))
-			.unwrap();
-		if update_car_items(server, car.clone(), None, vec![item.clone().into()])
-			.await
-			.is_ok()
-		{
-			car_items.push(item.clone().into());
-			new_item_buf.clear();
-		}
+			.map(|item| item.clone().into())
+	} else {
+		None
 	}
 }
 
-async fn wait_update_items(
-	car_items: &mut Vec<wm::CarItem>,
+fn wait_update_items(
+	car_items: &[wm::CarItem],
 	category: wm::ItemCategory,
 	ui: &mut egui::Ui,
 	new_item_buf: &mut String,
 	cars: &wm::Cars,
-	server: &Url,
-	car: &wm::Car,
-) {
+) -> Option<wm::CarItem> {
 	let mut new_car_items = car_items
 		.iter()
-		.filter(|i| i.category == category.into())
+		.filter(|i| i.category() == category)
 		.collect::<Vec<_>>();
 	new_car_items.sort_by(|a, b| a.item_id.cmp(&b.item_id));
 	if category == wm::ItemCategory::CatAuraMotif {
@@ -284,7 +321,7 @@ async fn wait_update_items(
 	} else if category == wm::ItemCategory::CatCustomFrame {
 		show_enum_items::<wm::CustomFrame>(&new_car_items, ui, new_item_buf);
 	} else if category == wm::ItemCategory::CatAero {
-		show_dress_up_items(car_items, ui, new_item_buf, cars, server, car).await;
+		return show_dress_up_items(car_items, ui, new_item_buf, cars);
 	} else {
 		if !car_items.is_empty() {
 			ui.heading("Current items");
@@ -294,6 +331,17 @@ async fn wait_update_items(
 		}
 		ui.text_edit_singleline(new_item_buf);
 	}
+
+	if ui.button("Add item").clicked() {
+		let item_id = new_item_buf.parse().ok()?;
+		Some(wm::CarItem {
+			category: category.into(),
+			item_id,
+			amount: Some(1),
+		})
+	} else {
+		None
+	}
 }
 
 fn wait_select_category(ui: &mut egui::Ui) -> Option<wm::ItemCategory> {
@@ -319,6 +367,8 @@ fn wait_select_category(ui: &mut egui::Ui) -> Option<wm::ItemCategory> {
 pub struct ItemMenu {
 	pub new_item_buf: String,
 	pub selected_category: Option<wm::ItemCategory>,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<Vec<wm::CarItem>>>,
 }
 
 impl ItemMenu {
@@ -326,11 +376,22 @@ impl ItemMenu {
 		&mut self,
 		ui: &mut egui::Ui,
 		runtime: &tokio::runtime::Runtime,
-		server: &Url,
+		client: &client::WmClient,
 		car: &wm::Car,
 		car_items: &mut Vec<wm::CarItem>,
 	) {
-		runtime.block_on(async {
+		match task::poll(&mut self.pending) {
+			Some(Ok(mut items)) => {
+				car_items.append(&mut items);
+				self.new_item_buf.clear();
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+
+		let mut new_items = None;
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
 			if self.selected_category.is_none() {
 				self.selected_category = items::wait_select_category(ui);
 				if car_items
@@ -339,70 +400,41 @@ impl ItemMenu {
 					.collect::<Vec<_>>()
 					.len() != 40 && ui.button("Give all custom colors").clicked()
 				{
-					give_all_colors(server, car, car_items).await;
+					new_items = Some(missing_colors(car_items));
 				}
 			} else if let Some(selected_category) = self.selected_category {
-				items::wait_update_items(
+				let cars = wm::Cars::from_u32(car.visual_model()).unwrap();
+				if let Some(item) = items::wait_update_items(
 					car_items,
 					selected_category,
 					ui,
 					&mut self.new_item_buf,
-					&wm::Cars::from_u32(car.visual_model()).unwrap(),
-					server,
-					car,
-				)
-				.await;
-
-				if selected_category != wm::ItemCategory::CatAero && ui.button("Add item").clicked()
-				{
-					let item_id = match self.new_item_buf.parse() {
-						Ok(item) => item,
-						Err(_) => return,
-					};
-					let item = wm::CarItem {
-						category: selected_category.into(),
-						item_id,
-						amount: Some(1),
-					};
-					if update_car_items(server, car.clone(), None, vec![item.clone()])
-						.await
-						.is_ok()
-					{
-						car_items.push(item);
-						self.new_item_buf.clear();
-					}
+					&cars,
+				) {
+					new_items = Some(vec![item]);
 				}
+
 				if ui.button("Give all").clicked() {
-					if selected_category == wm::ItemCategory::CatAuraMotif {
-						give_all_vs_items(server, car, car_items).await;
-					} else if selected_category == wm::ItemCategory::CatBgm {
-						give_all_enum_items::<wm::Bgms>(server, car, car_items).await;
-					} else if selected_category == wm::ItemCategory::CatMeter {
-						give_all_enum_items::<wm::Meters>(server, car, car_items).await;
-					} else if selected_category == wm::ItemCategory::CatNamePlate {
-						give_all_enum_items::<wm::Nameplates>(server, car, car_items).await;
-					} else if selected_category == wm::ItemCategory::CatRivalMarker {
-						give_all_enum_items::<wm::RivalMarker>(server, car, car_items).await;
-					} else if selected_category == wm::ItemCategory::CatCustomFrame {
-						give_all_enum_items::<wm::CustomFrame>(server, car, car_items).await;
-					} else if selected_category == wm::ItemCategory::CatAero {
-						let mut earned_items = vec![];
-						let cars = wm::Cars::from_u32(car.visual_model()).unwrap();
-						for item in wm::DU_ITEMS {
-							if cars.can_use_du_item(&item) {
-								earned_items.push(item.into());
-							}
-						}
-						if update_car_items(server, car.clone(), None, earned_items.clone())
-							.await
-							.is_ok()
-						{
-							car_items.append(&mut earned_items);
-						}
-					}
+					new_items = Some(missing_items(selected_category, &cars, car_items));
 				}
 			}
 		});
+
+		if let Some(items) = new_items
+			&& !items.is_empty()
+		{
+			let client = client.clone();
+			let car = car.clone();
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				update_car_items(&client, car, None, items.clone()).await?;
+				Ok(items)
+			}));
+		}
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
 	}
 
 	pub fn back(&mut self) -> bool {
@@ -415,8 +447,8 @@ impl ItemMenu {
 	}
 }
 
-async fn update_car_items(
-	server: &Url,
+pub async fn update_car_items(
+	client: &client::WmClient,
 	car: wm::Car,
 	setting: Option<wm::CarSetting>,
 	earned_items: Vec<wm::CarItem>,
@@ -429,5 +461,42 @@ async fn update_car_items(
 		..Default::default()
 	};
 
-	wm::send_request(req, server, "method/update_car").await
+	client.update_car(req).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn update_car_items_grants_items() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let car = server
+			.state
+			.lock()
+			.unwrap()
+			.car(mock::CAR_ID)
+			.unwrap()
+			.car
+			.clone();
+		let items = missing_enum_items::<wm::Bgms>(&[]);
+
+		update_car_items(&server.client(), car, None, items.clone())
+			.await
+			.unwrap();
+
+		let state = server.state.lock().unwrap();
+		assert_eq!(state.car(mock::CAR_ID).unwrap().owned_items, items);
+	}
+
+	#[test]
+	fn missing_items_skip_owned_ones() {
+		let owned = missing_vs_items(&[]);
+		assert!(missing_vs_items(&owned).is_empty());
+
+		let cars = wm::Cars::FD3S;
+		let du_items = missing_du_items(&cars, &[]);
+		assert!(!du_items.is_empty());
+		assert!(missing_du_items(&cars, &du_items).is_empty());
+	}
 }
@@ -0,0 +1,183 @@
+//! Logging in by typing a card's access code and chip ID instead of dropping a card.ini.
+use crate::*;
+use anyhow::{bail, Context, Result};
+use eframe::egui;
+
+#[derive(Default)]
+pub struct LoginForm {
+	pub access_code: String,
+	pub chip_id: String,
+	pub card_type_code: String,
+	pub card_region_code: String,
+	pub device: wm::DeviceType,
+	pub error: Option<String>,
+	pub pending_lookup: Option<task::Task<String>>,
+}
+
+impl LoginForm {
+	pub fn card(&self) -> Result<Card> {
+		let access_code = self.access_code.trim();
+		if access_code.len() != 20 || !access_code.chars().all(|c| c.is_ascii_digit()) {
+			bail!("Access code must be 20 digits");
+		}
+		let chip_id = self.chip_id.trim();
+		if chip_id.is_empty() || !chip_id.chars().all(|c| c.is_ascii_hexdigit()) {
+			bail!("Chip ID must be hexadecimal");
+		}
+
+		Ok(Card {
+			access_code: String::from(access_code),
+			chip_id: String::from(chip_id),
+			card_type_code: parse_code(&self.card_type_code).context("Invalid card type code")?,
+			card_region_code: parse_code(&self.card_region_code)
+				.context("Invalid card region code")?,
+			device: Some(self.device.into()),
+		})
+	}
+
+	/// Returns the card to log in with once "Log in" is clicked with valid input.
+	pub fn show(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+	) -> Option<Card> {
+		match task::poll(&mut self.pending_lookup) {
+			Some(Ok(access_code)) => {
+				self.access_code = access_code;
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+
+		let mut card = None;
+
+		egui::CollapsingHeader::new("Log in by access code").show(ui, |ui| {
+			egui::Grid::new("LoginGrid").num_columns(2).show(ui, |ui| {
+				ui.label("Access code");
+				ui.text_edit_singleline(&mut self.access_code);
+				ui.end_row();
+
+				ui.label("Chip ID");
+				ui.text_edit_singleline(&mut self.chip_id);
+				ui.end_row();
+
+				ui.label("Card type code");
+				ui.add(egui::TextEdit::singleline(&mut self.card_type_code).hint_text("None"));
+				ui.end_row();
+
+				ui.label("Card region code");
+				ui.add(egui::TextEdit::singleline(&mut self.card_region_code).hint_text("None"));
+				ui.end_row();
+
+				ui.label("Device");
+				egui::ComboBox::from_id_source("DeviceComboBox")
+					.selected_text(self.device.as_str_name())
+					.show_ui(ui, |ui| {
+						for device in [wm::DeviceType::DeviceCard, wm::DeviceType::DevicePhone] {
+							ui.selectable_value(&mut self.device, device, device.as_str_name());
+						}
+					});
+				ui.end_row();
+			});
+
+			ui.horizontal(|ui| {
+				if ui.button("Log in").clicked() {
+					match self.card() {
+						Ok(new_card) => {
+							self.error = None;
+							card = Some(new_card);
+						}
+						Err(err) => self.error = Some(err.to_string()),
+					}
+				}
+
+				ui.add_enabled_ui(self.pending_lookup.is_none(), |ui| {
+					if ui.button("Look up access code").clicked() {
+						let client = client.clone();
+						let chip_id = String::from(self.chip_id.trim());
+						self.error = None;
+						self.pending_lookup =
+							Some(task::Task::spawn(runtime, ui.ctx(), async move {
+								ask_access_code(&client, chip_id).await
+							}));
+					}
+				});
+				if self.pending_lookup.is_some() {
+					ui.spinner();
+				}
+			});
+
+			show_error(ui, &self.error);
+		});
+
+		card
+	}
+}
+
+fn parse_code(code: &str) -> Result<Option<u32>> {
+	match code.trim() {
+		"" => Ok(None),
+		code => Ok(Some(code.parse()?)),
+	}
+}
+
+async fn ask_access_code(client: &client::WmClient, card_chip_id: String) -> Result<String> {
+	if card_chip_id.is_empty() {
+		bail!("Enter a chip ID to look up");
+	}
+	let res = client
+		.ask_access_code(wm::AskAccessCodeRequest { card_chip_id })
+		.await?;
+
+	Ok(res.access_code)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn card_is_validated() {
+		let mut form = LoginForm {
+			access_code: String::from(mock::ACCESS_CODE),
+			chip_id: String::from(mock::CHIP_ID),
+			card_type_code: String::from("20"),
+			..Default::default()
+		};
+		let card = form.card().unwrap();
+		assert_eq!(card.card_type_code, Some(20));
+		assert_eq!(card.card_region_code, None);
+
+		form.card_region_code = String::from("JPN");
+		assert!(form.card().is_err());
+		form.card_region_code = String::new();
+		form.access_code = String::from("1234");
+		assert!(form.card().is_err());
+	}
+
+	#[tokio::test]
+	async fn looks_up_and_logs_in_by_chip_id() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+
+		let access_code = ask_access_code(&client, String::from(mock::CHIP_ID))
+			.await
+			.unwrap();
+		assert_eq!(access_code, mock::ACCESS_CODE);
+		assert!(ask_access_code(&client, String::from("FFFF"))
+			.await
+			.is_err());
+
+		let form = LoginForm {
+			access_code,
+			chip_id: String::from(mock::CHIP_ID),
+			..Default::default()
+		};
+		let user = load_user_with_card(&form.card().unwrap(), &client)
+			.await
+			.unwrap();
+		assert_eq!(user.user_id(), mock::USER_ID);
+	}
+}
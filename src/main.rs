@@ -2,14 +2,39 @@
 use anyhow::Result;
 use eframe::egui;
 use num_traits::FromPrimitive;
-use url::Url;
 
+pub mod backup;
 pub mod car;
+pub mod cli;
+pub mod garage;
+pub mod ghost;
+pub mod highway;
+pub mod history;
+pub mod inbox;
 pub mod items;
+pub mod login;
+pub mod newcar;
+pub mod scratch;
+pub mod settings;
+pub mod story;
+pub mod task;
+pub mod timeattack;
 pub mod useritems;
-pub mod wm;
+
+#[cfg(test)]
+use hax_6rr_core::mock;
+pub use hax_6rr_core::{client, diff, endpoint, error, snapshot, wm};
 
 fn main() {
+	let args = std::env::args().skip(1).collect::<Vec<_>>();
+	if !args.is_empty() {
+		if let Err(err) = cli::run(&args) {
+			eprintln!("{err}");
+			std::process::exit(1);
+		}
+		return;
+	}
+
 	let native_options = eframe::NativeOptions {
 		viewport: egui::ViewportBuilder::default().with_drag_and_drop(true),
 		..Default::default()
@@ -48,27 +73,27 @@ fn main() {
 			Box::new(App {
 				runtime,
 				server_buf: String::new(),
-				server: None,
+				settings: settings::Settings::load(),
+				profile_form: settings::ProfileForm::default(),
+				login_form: login::LoginForm::default(),
+				client: None,
+				card: None,
+				user_id: None,
 				cars: Vec::new(),
+				car_states: Vec::new(),
 				car: None,
 				car_setting: None,
 				car_items: Vec::new(),
-				car_play_count: 0,
-				car_odometer: 0,
-				vs_star_count: 0,
-				vs_cool_or_wild: 0,
-				vs_smooth_or_rough: 0,
-				vs_gold_medal: 0,
-				vs_silver_medal: 0,
-				vs_bronze_medal: 0,
-				vs_plain_medal: 0,
-				vs_play_count: 0,
+				loaded_car: None,
 				sub_menu: None,
 				user_items: Vec::new(),
 				glb_enabled: false,
 				custom: false,
 				custom_color: false,
 				force: false,
+				error: None,
+				pending_user: None,
+				pending_car: None,
 			})
 		}),
 	)
@@ -78,38 +103,51 @@ fn main() {
 struct App {
 	runtime: tokio::runtime::Runtime,
 	server_buf: String,
-	server: Option<Url>,
+	settings: settings::Settings,
+	profile_form: settings::ProfileForm,
+	login_form: login::LoginForm,
+	client: Option<client::WmClient>,
+	/// The card logged in with, even if the server has no user for it yet.
+	card: Option<settings::RecentCard>,
+	user_id: Option<u32>,
 	cars: Vec<wm::Car>,
+	car_states: Vec<wm::load_user_response::CarState>,
 	car: Option<wm::Car>,
 	car_setting: Option<wm::CarSetting>,
 	car_items: Vec<wm::CarItem>,
-	car_play_count: u32,
-	car_odometer: u32,
-	vs_star_count: u32,
-	vs_cool_or_wild: i32,
-	vs_smooth_or_rough: i32,
-	vs_gold_medal: u32,
-	vs_silver_medal: u32,
-	vs_bronze_medal: u32,
-	vs_plain_medal: u32,
-	vs_play_count: u32,
+	/// The car as the server last returned it, before any edits.
+	loaded_car: Option<wm::LoadCarResponse>,
 	sub_menu: Option<SubMenu>,
 	user_items: Vec<wm::UserItem>,
 	glb_enabled: bool,
 	custom: bool,
 	custom_color: bool,
 	force: bool,
+	error: Option<String>,
+	/// `None` for the user means the card is blank.
+	pending_user: Option<task::Task<(settings::RecentCard, Option<wm::LoadUserResponse>)>>,
+	pending_car: Option<task::Task<wm::LoadCarResponse>>,
 }
 
 enum SubMenu {
 	Items(items::ItemMenu),
 	Car(car::CarMenu),
 	UserItems(useritems::UserItems),
+	Backup(Box<backup::BackupMenu>),
+	NewCar(Box<newcar::NewCarMenu>),
+	Garage(Box<garage::GarageMenu>),
+	Story(story::StoryMenu),
+	GhostBattle(ghost::GhostBattleMenu),
+	Highway(highway::HighwayMenu),
+	History(Box<history::HistoryMenu>),
+	TimeAttack(Box<timeattack::TimeAttackMenu>),
+	Scratch(Box<scratch::ScratchMenu>),
+	Inbox(Box<inbox::InboxMenu>),
 }
 
 impl eframe::App for App {
 	fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-		if !self.cars.is_empty() {
+		if self.card.is_some() {
 			egui::TopBottomPanel::top("TopPanel").show(ctx, |ui| {
 				egui::Grid::new("TopGrid").num_columns(2).show(ui, |ui| {
 					if ui.button("Back").clicked() {
@@ -118,12 +156,26 @@ impl eframe::App for App {
 								SubMenu::Items(items) => items.back(),
 								SubMenu::Car(car) => car.back(),
 								SubMenu::UserItems(useritems) => useritems.back(),
+								SubMenu::Backup(backup) => backup.back(),
+								SubMenu::NewCar(_) => true,
+								SubMenu::Garage(garage) => garage.back(),
+								SubMenu::Story(story) => story.back(),
+								SubMenu::GhostBattle(ghost) => ghost.back(),
+								SubMenu::Highway(highway) => highway.back(),
+								SubMenu::History(history) => history.back(),
+								SubMenu::TimeAttack(timeattack) => timeattack.back(),
+								SubMenu::Scratch(scratch) => scratch.back(),
+								SubMenu::Inbox(inbox) => inbox.back(),
 							};
 							if want_to_exit {
 								self.sub_menu = None;
 							}
 						} else if self.car.is_none() {
+							self.card = None;
+							self.user_id = None;
 							self.cars = Vec::new();
+							self.car_states = Vec::new();
+							self.user_items = Vec::new();
 						} else {
 							self.car = None;
 						}
@@ -140,19 +192,25 @@ impl eframe::App for App {
 
 		egui::CentralPanel::default().show(ctx, |ui| {
 			egui::ScrollArea::vertical().show(ui, |ui| {
+				let mut restored = None;
+				let mut created = None;
+				let mut reloaded = None;
+				let mut progress_saved = None;
+
 				if let Some(sub_menu) = &mut self.sub_menu {
 					match sub_menu {
 						SubMenu::Items(menu) => menu.update(
 							ui,
 							&self.runtime,
-							self.server.as_ref().unwrap(),
+							self.client.as_ref().unwrap(),
 							self.car.as_ref().unwrap(),
 							&mut self.car_items,
 						),
 						SubMenu::Car(menu) => menu.update(
 							ui,
 							&self.runtime,
-							self.server.as_ref().unwrap(),
+							self.client.as_ref().unwrap(),
+							self.loaded_car.as_mut().unwrap(),
 							self.car.as_mut().unwrap(),
 							self.car_setting.as_mut().unwrap(),
 							&self.car_items,
@@ -164,106 +222,358 @@ impl eframe::App for App {
 						SubMenu::UserItems(menu) => menu.update(
 							ui,
 							&self.runtime,
-							self.server.as_ref().unwrap(),
+							self.client.as_ref().unwrap(),
 							&mut self.user_items,
 							self.cars.first().as_ref().unwrap(),
-							self.car_odometer,
+							self.loaded_car.as_ref().map_or(0, |car| car.odometer),
 						),
+						SubMenu::Backup(menu) => {
+							restored = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								self.car.as_ref().unwrap().car_id(),
+							)
+						}
+						SubMenu::NewCar(menu) => {
+							created = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								&self.cars,
+								&self.user_items,
+							)
+						}
+						SubMenu::Story(menu) => {
+							progress_saved = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								self.loaded_car.as_ref().unwrap(),
+							)
+						}
+						SubMenu::GhostBattle(menu) => {
+							progress_saved = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								self.loaded_car.as_ref().unwrap(),
+							)
+						}
+						SubMenu::Highway(menu) => {
+							progress_saved = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								self.loaded_car.as_ref().unwrap(),
+							)
+						}
+						SubMenu::History(menu) => menu.update(
+							ui,
+							&self.runtime,
+							self.client.as_ref().unwrap(),
+							self.loaded_car.as_ref().unwrap(),
+						),
+						SubMenu::TimeAttack(menu) => menu.update(
+							ui,
+							&self.runtime,
+							self.client.as_ref().unwrap(),
+							self.loaded_car.as_ref().unwrap(),
+						),
+						SubMenu::Scratch(menu) => {
+							reloaded = menu.update(ui, &self.runtime, self.client.as_ref().unwrap())
+						}
+						SubMenu::Inbox(menu) => {
+							reloaded = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								&self.cars,
+							)
+						}
+						SubMenu::Garage(menu) => {
+							reloaded = menu.update(
+								ui,
+								&self.runtime,
+								self.client.as_ref().unwrap(),
+								&self.cars,
+								&self.car_states,
+							)
+						}
 					}
-				} else {
-					self.runtime.block_on(async {
-						if self.server.is_none() {
-							if ui
-								.add(
-									egui::TextEdit::singleline(&mut self.server_buf)
-										.hint_text("Server URL"),
-								)
-								.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))
-							{
-								if !self.server_buf.starts_with("http://")
-									|| !self.server_buf.starts_with("https://")
-								{
-									self.server_buf.insert_str(0, "https://")
-								}
-								if let Some(mut server) = Url::parse(&self.server_buf).ok() {
-									if server.port().is_none()
-										&& server.set_port(Some(9002)).is_err()
-									{
-										return;
-									}
-									self.server = Some(server);
-								}
+				} else if self.client.is_none() {
+					match settings::select_server_profile(ui, &mut self.settings) {
+						Ok(Some(profile)) => match profile.client() {
+							Ok(client) => {
+								self.client = Some(client);
+								self.error = None;
 							}
-						} else if self.cars.is_empty() {
-							let user = wait_user(ui, ctx, self.server.as_ref().unwrap()).await;
-							if let Some(user) = user {
-								self.cars = user.cars;
-								self.user_items = user.unused_car_tickets;
+							Err(err) => self.error = Some(err.to_string()),
+						},
+						Ok(None) => {}
+						Err(err) => self.error = Some(err.to_string()),
+					}
+
+					if ui
+						.add(
+							egui::TextEdit::singleline(&mut self.server_buf)
+								.hint_text("Server URL"),
+						)
+						.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter))
+					{
+						match endpoint::ServerEndpoint::parse(&self.server_buf)
+							.and_then(|endpoint| client::WmClient::new(&endpoint))
+						{
+							Ok(client) => {
+								self.client = Some(client);
+								self.error = None;
 							}
-						} else if self.car.is_none() {
-							let car =
-								wait_select_car(&self.cars, ui, self.server.as_ref().unwrap())
-									.await;
-							if let Some(car) = car {
-								self.car = Some(car.car);
-								self.car_setting = Some(car.setting);
-								self.car_items = car.owned_items;
-								self.car_play_count = car.play_count;
-								self.car_odometer = car.odometer;
-								self.vs_cool_or_wild = car.vs_cool_or_wild;
-								self.vs_smooth_or_rough = car.vs_smooth_or_rough;
-								self.vs_star_count = car.vs_star_count;
-								self.vs_play_count = car.vs_play_count;
-								self.vs_gold_medal = car.vs_triple_star_medals;
-								self.vs_silver_medal = car.vs_double_star_medals;
-								self.vs_bronze_medal = car.vs_single_star_medals;
-								self.vs_plain_medal = car.vs_plain_medals;
+							Err(err) => self.error = Some(err.to_string()),
+						}
+					}
+
+					if let Some(profile) = self.profile_form.show(ui) {
+						self.settings.servers.push(profile);
+						if let Err(err) = self.settings.save() {
+							self.error = Some(err.to_string());
+						}
+					}
+					show_error(ui, &self.error);
+				} else if self.card.is_none() {
+					match task::poll(&mut self.pending_user) {
+						Some(Ok((recent, user))) => {
+							self.error = None;
+							self.card = Some(recent.clone());
+							if let Some(user) = user {
+								self.set_user(user);
 							}
-							if ui.button("User Items").clicked() {
-								self.sub_menu = Some(SubMenu::UserItems(useritems::UserItems {
-									selected_category: None,
-									new_item_buf: None,
-								}));
+							self.settings.add_recent_card(recent);
+							if let Err(err) = self.settings.save() {
+								self.error = Some(err.to_string());
 							}
-						} else if self.sub_menu.is_none() {
-							if ui.button("Items").clicked() {
-								self.sub_menu = Some(SubMenu::Items(items::ItemMenu {
-									new_item_buf: String::new(),
-									selected_category: None,
-								}));
-							} else if ui.button("Car").clicked() {
-								self.sub_menu = Some(SubMenu::Car(car::CarMenu {
-									play_count: self.car_play_count,
-									odometer: self.car_odometer,
-									vs_cool_or_wild: self.vs_cool_or_wild,
-									vs_smooth_or_rough: self.vs_smooth_or_rough,
-									vs_play_count: self.vs_play_count,
-									vs_star_count: self.vs_star_count,
-									odometer_buf: self.car_odometer.to_string(),
-									vs_star_count_buf: self.vs_star_count.to_string(),
-									vs_gold_medal: self.vs_gold_medal,
-									vs_silver_medal: self.vs_silver_medal,
-									vs_bronze_medal: self.vs_bronze_medal,
-									vs_plain_medal: self.vs_plain_medal,
-									gold_medal_buf: self.vs_gold_medal.to_string(),
-									silver_medal_buf: self.vs_silver_medal.to_string(),
-									bronze_medal_buf: self.vs_bronze_medal.to_string(),
-									plain_medal_buf: self.vs_plain_medal.to_string(),
+						}
+						Some(Err(err)) => self.error = Some(err.to_string()),
+						None => {}
+					}
+
+					if self.pending_user.is_some() {
+						ui.spinner();
+					} else if let Some(path) = wait_user(ui, ctx) {
+						let client = self.client.clone().unwrap();
+						self.pending_user =
+							Some(task::Task::spawn(&self.runtime, ctx, async move {
+								let card = read_card(&path).await?;
+								let user = find_user(&card, &client).await?;
+								let recent = settings::RecentCard {
+									path: Some(path),
+									card,
+								};
+								Ok((recent, user))
+							}));
+					} else if let Some(recent) = settings::select_recent_card(ui, &self.settings) {
+						let client = self.client.clone().unwrap();
+						self.pending_user =
+							Some(task::Task::spawn(&self.runtime, ctx, async move {
+								let user = find_user(&recent.card, &client).await?;
+								Ok((recent, user))
+							}));
+					} else if let Some(card) =
+						self.login_form
+							.show(ui, &self.runtime, self.client.as_ref().unwrap())
+					{
+						let client = self.client.clone().unwrap();
+						self.pending_user =
+							Some(task::Task::spawn(&self.runtime, ctx, async move {
+								let user = find_user(&card, &client).await?;
+								let recent = settings::RecentCard { path: None, card };
+								Ok((recent, user))
+							}));
+					}
+					show_error(ui, &self.error);
+				} else if self.user_id.is_none() {
+					match task::poll(&mut self.pending_user) {
+						Some(Ok((_, Some(user)))) => {
+							self.error = None;
+							self.set_user(user);
+						}
+						Some(Ok((_, None))) => {
+							self.error = Some(String::from("Server didn't create the user"))
+						}
+						Some(Err(err)) => self.error = Some(err.to_string()),
+						None => {}
+					}
+
+					ui.heading("No user is registered to this card");
+					ui.add_enabled_ui(self.pending_user.is_none(), |ui| {
+						if ui.button("Create user").clicked() {
+							let client = self.client.clone().unwrap();
+							let recent = self.card.clone().unwrap();
+							self.pending_user =
+								Some(task::Task::spawn(&self.runtime, ctx, async move {
+									let user = create_user(&recent.card, &client).await?;
+									Ok((recent, Some(user)))
 								}));
-							}
 						}
 					});
+					if self.pending_user.is_some() {
+						ui.spinner();
+					}
+					show_error(ui, &self.error);
+				} else if self.car.is_none() {
+					match task::poll(&mut self.pending_car) {
+						Some(Ok(car)) => {
+							self.error = None;
+							self.set_car(car);
+						}
+						Some(Err(err)) => self.error = Some(err.to_string()),
+						None => {}
+					}
+
+					let car_id = ui
+						.add_enabled_ui(self.pending_car.is_none(), |ui| {
+							wait_select_car(&self.cars, ui)
+						})
+						.inner;
+					if let Some(car_id) = car_id {
+						let client = self.client.clone().unwrap();
+						self.pending_car =
+							Some(task::Task::spawn(&self.runtime, ctx, async move {
+								load_car(car_id, &client).await
+							}));
+					}
+					if self.pending_car.is_some() {
+						ui.spinner();
+					}
+					if ui.button("New car").clicked() {
+						self.sub_menu = Some(SubMenu::NewCar(Box::new(newcar::NewCarMenu::new(
+							self.card.clone().unwrap().card,
+							self.user_id.unwrap(),
+						))));
+					}
+					if !self.cars.is_empty() && ui.button("Garage").clicked() {
+						self.sub_menu = Some(SubMenu::Garage(Box::new(garage::GarageMenu::new(
+							self.card.clone().unwrap().card,
+							self.user_id.unwrap(),
+							&self.cars,
+						))));
+					}
+					if !self.cars.is_empty() && ui.button("User Items").clicked() {
+						self.sub_menu = Some(SubMenu::UserItems(useritems::UserItems::new(
+							self.user_id.unwrap(),
+						)));
+					}
+					if !self.cars.is_empty() && ui.button("Inbox").clicked() {
+						self.sub_menu = Some(SubMenu::Inbox(Box::new(inbox::InboxMenu::new(
+							self.card.clone().unwrap().card,
+							self.user_id.unwrap(),
+						))));
+					}
+					if ui.button("Scratch").clicked() {
+						self.sub_menu = Some(SubMenu::Scratch(Box::new(
+							scratch::ScratchMenu::new(
+								self.card.clone().unwrap().card,
+								self.user_id.unwrap(),
+							),
+						)));
+					}
+					show_error(ui, &self.error);
+				} else if ui.button("Items").clicked() {
+					self.sub_menu = Some(SubMenu::Items(items::ItemMenu {
+						new_item_buf: String::new(),
+						selected_category: None,
+						error: None,
+						pending: None,
+					}));
+				} else if ui.button("Car").clicked() {
+					self.sub_menu = Some(SubMenu::Car(car::CarMenu::new(
+						self.loaded_car.as_ref().unwrap(),
+						&self.cars,
+					)));
+				} else if ui.button("Story").clicked() {
+					self.sub_menu = Some(SubMenu::Story(story::StoryMenu::new(
+						self.loaded_car.as_ref().unwrap(),
+					)));
+				} else if ui.button("Ghost Battle").clicked() {
+					self.sub_menu = Some(SubMenu::GhostBattle(ghost::GhostBattleMenu::new(
+						self.loaded_car.as_ref().unwrap(),
+					)));
+				} else if ui.button("Ghost Highway").clicked() {
+					self.sub_menu = Some(SubMenu::Highway(highway::HighwayMenu::new(
+						self.loaded_car.as_ref().unwrap(),
+					)));
+				} else if ui.button("History").clicked() {
+					self.sub_menu = Some(SubMenu::History(Box::default()));
+				} else if ui.button("Time Attack").clicked() {
+					self.sub_menu = Some(SubMenu::TimeAttack(Box::default()));
+				} else if ui.button("Backup").clicked() {
+					self.sub_menu = Some(SubMenu::Backup(Box::default()));
+				}
+
+				if let Some(car) = restored {
+					self.set_car(car);
+				}
+				if let Some(user) = created {
+					self.set_user(user);
+					self.sub_menu = None;
+				}
+				if let Some(user) = reloaded {
+					self.set_user(user);
+				}
+				// Only progress fields changed, so edits in the other menus stay unsaved
+				if let Some(car) = progress_saved {
+					self.loaded_car = Some(car);
 				}
 			});
 		});
 	}
 }
 
-async fn wait_user(
-	ui: &mut egui::Ui,
-	ctx: &egui::Context,
-	server: &Url,
-) -> Option<wm::LoadUserResponse> {
+impl App {
+	fn set_user(&mut self, user: wm::LoadUserResponse) {
+		self.user_id = user.user_id;
+		self.cars = user.cars;
+		self.car_states = user.car_states;
+		self.user_items = user.unused_car_tickets;
+	}
+
+	fn set_car(&mut self, car: wm::LoadCarResponse) {
+		self.glb_enabled = car::is_glb(&car.car);
+		self.car = Some(car.car.clone());
+		self.car_setting = Some(car.setting.clone());
+		self.car_items = car.owned_items.clone();
+		self.loaded_car = Some(car);
+	}
+}
+
+pub fn show_error(ui: &mut egui::Ui, error: &Option<String>) {
+	if let Some(error) = error {
+		ui.colored_label(egui::Color32::RED, error);
+	}
+}
+
+/// Lists the protocol only gives as numbers, shown as cells under their position.
+pub fn show_numbered(ui: &mut egui::Ui, id: &str, values: &[u32]) {
+	const PER_ROW: usize = 10;
+
+	if values.is_empty() {
+		ui.label("None");
+		return;
+	}
+	egui::Grid::new(id).striped(true).show(ui, |ui| {
+		for (row, chunk) in values.chunks(PER_ROW).enumerate() {
+			for i in 0..chunk.len() {
+				ui.weak((row * PER_ROW + i + 1).to_string());
+			}
+			ui.end_row();
+			for value in chunk {
+				ui.label(value.to_string());
+			}
+			ui.end_row();
+		}
+	});
+}
+
+fn wait_user(ui: &mut egui::Ui, ctx: &egui::Context) -> Option<String> {
 	ui.heading("Drop card.ini onto window");
 
 	for file in ctx.input(|i| i.raw.dropped_files.clone()) {
@@ -275,20 +585,13 @@ async fn wait_user(
 			Some(path) => path,
 			None => continue,
 		};
-		let user = load_user(path, server).await;
-		if let Ok(user) = user {
-			return Some(user);
-		}
+		return Some(String::from(path));
 	}
 
 	None
 }
 
-async fn wait_select_car(
-	cars: &[wm::Car],
-	ui: &mut egui::Ui,
-	server: &Url,
-) -> Option<wm::LoadCarResponse> {
+fn wait_select_car(cars: &[wm::Car], ui: &mut egui::Ui) -> Option<u32> {
 	for car in cars.iter() {
 		if ui
 			.button(format!(
@@ -298,25 +601,29 @@ async fn wait_select_car(
 			))
 			.clicked()
 		{
-			let car = load_car(car.car_id(), server).await;
-			if let Ok(car) = car {
-				return Some(car);
-			}
+			return Some(car.car_id());
 		}
 	}
 
 	None
 }
 
-async fn load_user(ini_path: &str, server: &Url) -> Result<wm::LoadUserResponse> {
-	#[derive(serde::Deserialize)]
-	struct Card {
-		#[serde(rename = "accessCode")]
-		access_code: String,
-		#[serde(rename = "chipId")]
-		chip_id: String,
-	}
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Card {
+	#[serde(rename = "accessCode")]
+	pub access_code: String,
+	#[serde(rename = "chipId")]
+	pub chip_id: String,
+	/// Only set by the login form, card.ini files don't have these.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub card_type_code: Option<u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub card_region_code: Option<u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub device: Option<i32>,
+}
 
+async fn read_card(ini_path: &str) -> Result<Card> {
 	#[derive(serde::Deserialize)]
 	struct CardHolder {
 		card: Card,
@@ -326,22 +633,155 @@ async fn load_user(ini_path: &str, server: &Url) -> Result<wm::LoadUserResponse>
 
 	let card: CardHolder = serde_ini::from_str(&ini)?;
 
+	Ok(card.card)
+}
+
+async fn load_user(ini_path: &str, client: &client::WmClient) -> Result<wm::LoadUserResponse> {
+	load_user_with_card(&read_card(ini_path).await?, client).await
+}
+
+async fn load_user_with_card(
+	card: &Card,
+	client: &client::WmClient,
+) -> Result<wm::LoadUserResponse> {
 	let req = wm::LoadUserRequest {
-		card_chip_id: Some(card.card.chip_id),
-		access_code: Some(card.card.access_code),
+		card_chip_id: Some(card.chip_id.clone()),
+		access_code: Some(card.access_code.clone()),
+		card_type_code: card.card_type_code,
+		card_region_code: card.card_region_code,
+		device: card.device,
 		max_cars: 255,
 		create_user: Some(false),
 		..Default::default()
 	};
 
-	wm::send_request(req, server, "method/load_user").await
+	client.load_user(req).await
 }
 
-async fn load_car(car_id: u32, server: &Url) -> Result<wm::LoadCarResponse> {
+/// Like `load_user_with_card`, but a card the server doesn't know yet isn't an error.
+async fn find_user(card: &Card, client: &client::WmClient) -> Result<Option<wm::LoadUserResponse>> {
+	match load_user_with_card(card, client).await {
+		Ok(user) if user.user_id.is_some() => Ok(Some(user)),
+		Ok(_) => Ok(None),
+		Err(err) if err.downcast_ref() == Some(&error::WmError::NotFound) => Ok(None),
+		Err(err) => Err(err),
+	}
+}
+
+async fn create_user(card: &Card, client: &client::WmClient) -> Result<wm::LoadUserResponse> {
+	let req = wm::CreateUserRequest {
+		card_chip_id: Some(card.chip_id.clone()),
+		access_code: Some(card.access_code.clone()),
+		card_type_code: card.card_type_code,
+		card_region_code: card.card_region_code,
+		device: card.device,
+		timestamp: timestamp(),
+		..Default::default()
+	};
+	client.create_user(req).await?;
+
+	load_user_with_card(card, client).await
+}
+
+async fn load_car(car_id: u32, client: &client::WmClient) -> Result<wm::LoadCarResponse> {
 	let req = wm::LoadCarRequest {
 		car_id,
 		..Default::default()
 	};
 
-	wm::send_request(req, server, "method/load_car").await
+	client.load_car(req).await
+}
+
+/// Unix time in seconds, as the protocol's `timestamp` fields expect.
+pub fn timestamp() -> u32 {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|time| time.as_secs() as u32)
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn load_user_from_card_ini() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let path = std::env::temp_dir().join(format!("hax-6rr-{}-card.ini", std::process::id()));
+		tokio::fs::write(
+			&path,
+			format!(
+				"[card]\naccessCode={}\nchipId={}\n",
+				mock::ACCESS_CODE,
+				mock::CHIP_ID
+			),
+		)
+		.await
+		.unwrap();
+
+		let user = load_user(path.to_str().unwrap(), &server.client()).await;
+		_ = tokio::fs::remove_file(&path).await;
+		let user = user.unwrap();
+
+		assert_eq!(user.user_id, Some(mock::USER_ID));
+		assert_eq!(user.cars.len(), 1);
+		assert_eq!(user.cars[0].car_id(), mock::CAR_ID);
+	}
+
+	#[tokio::test]
+	async fn blank_card_can_create_user() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let card = Card {
+			access_code: String::from("00000000000000000001"),
+			chip_id: String::from("00"),
+			..Default::default()
+		};
+
+		assert_eq!(find_user(&card, &client).await.unwrap(), None);
+		let user = create_user(&card, &client).await.unwrap();
+		assert_eq!(user.user_id, Some(mock::USER_ID + 1));
+		assert!(user.cars.is_empty());
+		assert!(find_user(&card, &client).await.unwrap().is_some());
+	}
+
+	#[tokio::test]
+	async fn load_car_returns_server_state() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+
+		let car = load_car(mock::CAR_ID, &server.client()).await.unwrap();
+
+		assert_eq!(car.car.visual_model(), wm::Cars::FD3S as u32);
+		assert_eq!(car.odometer, 1000);
+	}
+
+	#[tokio::test]
+	async fn load_car_surfaces_error_code() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+
+		let err = load_car(mock::CAR_ID + 1, &server.client())
+			.await
+			.unwrap_err();
+
+		assert_eq!(
+			err.downcast_ref::<error::WmError>(),
+			Some(&error::WmError::CarNotFound)
+		);
+	}
+
+	#[tokio::test]
+	async fn failed_request_names_path_and_status() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+
+		let err = server
+			.client()
+			.resource::<wm::LoadUserResponse>("method/missing")
+			.await
+			.unwrap_err();
+
+		assert_eq!(
+			err.to_string(),
+			"Request to /wmmt6/method/missing failed with 404 Not Found"
+		);
+	}
 }
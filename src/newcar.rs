@@ -0,0 +1,351 @@
+//! Registering a new car on the logged in user, optionally spending a car ticket.
+use crate::*;
+use anyhow::{bail, Result};
+use eframe::egui;
+use num_traits::{FromPrimitive, ToPrimitive};
+use strum::IntoEnumIterator;
+
+const MAX_NAME_LEN: usize = 8;
+/// Cars a card holds, past this a new car has to replace one.
+const MAX_CARS: usize = 8;
+const FULL_TUNE_TICKET: u32 = 5;
+const DISCARDED_VEHICLE_TICKETS: [u32; 3] = [1, 2, 3];
+
+#[derive(Default)]
+pub struct NewCarMenu {
+	pub card: Card,
+	pub user_id: u32,
+	/// Only the fields the wizard offers are set, the server fills in the rest.
+	pub car: wm::Car,
+	pub name_buf: String,
+	pub glb_enabled: bool,
+	pub transmission: bool,
+	pub user_item_id: Option<u32>,
+	/// The car the new one replaces when the garage is full.
+	pub deleted_car_id: Option<u32>,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::LoadUserResponse>>,
+}
+
+impl NewCarMenu {
+	pub fn new(card: Card, user_id: u32) -> Self {
+		Self {
+			card,
+			user_id,
+			..Default::default()
+		}
+	}
+
+	/// The request for the car as entered so far, with `cars` being the user's garage.
+	///
+	/// `manufacturer` is left unset since there is no table of it per model here.
+	pub fn request(&self, cars: &[wm::Car]) -> Result<wm::CreateCarRequest> {
+		let card = &self.card;
+		let Some(model) = self.car.visual_model else {
+			bail!("Pick a model");
+		};
+		if self.car.region_id.is_none() {
+			bail!("Pick a region");
+		}
+		let name = full_width(self.name_buf.trim());
+		if name.is_empty() {
+			bail!("Enter a name");
+		}
+		if name.chars().count() > MAX_NAME_LEN {
+			bail!("Name can't be longer than {MAX_NAME_LEN} characters");
+		}
+		let deleted_car_id = self
+			.deleted_car_id
+			.filter(|car_id| cars.iter().any(|car| car.car_id() == *car_id));
+		if cars.len() >= MAX_CARS && deleted_car_id.is_none() {
+			bail!("The garage is full, pick a car to replace");
+		}
+
+		Ok(wm::CreateCarRequest {
+			user_id: Some(self.user_id),
+			card_chip_id: Some(card.chip_id.clone()),
+			access_code: Some(card.access_code.clone()),
+			card_type_code: card.card_type_code,
+			card_region_code: card.card_region_code,
+			device: card.device,
+			car: wm::Car {
+				name: Some(name),
+				manufacturer: None,
+				model: Some(model),
+				default_color: Some(self.car.default_color()),
+				..self.car.clone()
+			},
+			transmission: self.transmission,
+			timestamp: timestamp(),
+			user_item_id: self.user_item_id,
+			deleted_car_id,
+			..Default::default()
+		})
+	}
+
+	/// Returns the user as the server has it once the car was created.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		cars: &[wm::Car],
+		user_items: &[wm::UserItem],
+	) -> Option<wm::LoadUserResponse> {
+		let created = match task::poll(&mut self.pending) {
+			Some(Ok(user)) => {
+				self.error = None;
+				Some(user)
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
+			egui::Grid::new("NewCarGrid").num_columns(2).show(ui, |ui| {
+				set_model(ui, &mut self.car);
+
+				ui.label("Name");
+				ui.add(egui::TextEdit::singleline(&mut self.name_buf).char_limit(MAX_NAME_LEN));
+				ui.end_row();
+
+				car::set_region_and_country(ui, &mut self.car, &mut self.glb_enabled);
+
+				ui.label("Manual transmission");
+				ui.add(egui::Checkbox::without_text(&mut self.transmission));
+				ui.end_row();
+
+				set_ticket(ui, &mut self.user_item_id, user_items);
+
+				if cars.len() >= MAX_CARS {
+					set_replaced_car(ui, &mut self.deleted_car_id, cars);
+				}
+			});
+
+			if ui.button("Create car").clicked() {
+				match self.request(cars) {
+					Ok(req) => {
+						let client = client.clone();
+						let card = self.card.clone();
+						self.error = None;
+						self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+							create_car(&client, &card, req).await
+						}));
+					}
+					Err(err) => self.error = Some(err.to_string()),
+				}
+			}
+		});
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
+
+		created
+	}
+}
+
+fn set_model(ui: &mut egui::Ui, car: &mut wm::Car) {
+	ui.label("Model");
+	let selected = car
+		.visual_model
+		.and_then(wm::Cars::from_u32)
+		.map_or(String::new(), |model| model.to_string());
+	let old_model = car.visual_model;
+	egui::ComboBox::from_id_source("NewCarModelComboBox")
+		.selected_text(selected)
+		.show_ui(ui, |ui| {
+			for model in wm::Cars::iter() {
+				ui.selectable_value(&mut car.visual_model, model.to_u32(), model.to_string());
+			}
+		});
+	if car.visual_model != old_model {
+		car.model = car.visual_model;
+		car.default_color = Some(0);
+	}
+	ui.end_row();
+
+	ui.label("Color");
+	if let Some(model) = car.visual_model.and_then(wm::Cars::from_u32) {
+		let default_colors = model.default_colors();
+		if let Some(color) = default_colors.get(car.default_color() as usize) {
+			egui::ComboBox::from_id_source("NewCarColorComboBox")
+				.selected_text(&color.name)
+				.show_ui(ui, |ui| {
+					for (i, color) in default_colors.iter().enumerate() {
+						ui.selectable_value(&mut car.default_color, Some(i as u32), &color.name);
+					}
+				});
+		}
+	}
+	ui.end_row();
+}
+
+fn set_ticket(ui: &mut egui::Ui, user_item_id: &mut Option<u32>, user_items: &[wm::UserItem]) {
+	let tickets = user_items
+		.iter()
+		.filter(|item| item.category() == wm::ItemCategory::CatCarTicketFree)
+		.filter_map(|item| Some((item.user_item_id?, ticket_name(item.item_id)?)))
+		.collect::<Vec<_>>();
+
+	ui.label("Ticket");
+	let selected = tickets
+		.iter()
+		.find(|(id, _)| Some(*id) == *user_item_id)
+		.map_or("None", |(_, name)| name);
+	egui::ComboBox::from_id_source("NewCarTicketComboBox")
+		.selected_text(selected)
+		.show_ui(ui, |ui| {
+			ui.selectable_value(user_item_id, None, "None");
+			for (id, name) in tickets.iter() {
+				ui.selectable_value(user_item_id, Some(*id), format!("{name} #{id}"));
+			}
+		});
+	ui.end_row();
+}
+
+fn set_replaced_car(ui: &mut egui::Ui, deleted_car_id: &mut Option<u32>, cars: &[wm::Car]) {
+	ui.label("Replace");
+	let selected = cars
+		.iter()
+		.find(|car| Some(car.car_id()) == *deleted_car_id)
+		.map_or("", |car| car.name());
+	egui::ComboBox::from_id_source("NewCarReplaceComboBox")
+		.selected_text(selected)
+		.show_ui(ui, |ui| {
+			for car in cars.iter() {
+				ui.selectable_value(deleted_car_id, Some(car.car_id()), car.name());
+			}
+		});
+	ui.end_row();
+}
+
+pub fn ticket_name(item_id: u32) -> Option<&'static str> {
+	if item_id == FULL_TUNE_TICKET {
+		Some("Full Tune Ticket")
+	} else if DISCARDED_VEHICLE_TICKETS.contains(&item_id) {
+		Some("Discarded Vehicle Ticket")
+	} else {
+		None
+	}
+}
+
+/// Names are entered in full-width characters on the cabinet, so ASCII is widened to match.
+pub fn full_width(name: &str) -> String {
+	name.chars()
+		.map(|c| match c {
+			' ' => '\u{3000}',
+			'!'..='~' => char::from_u32(c as u32 + 0xfee0).unwrap_or(c),
+			c => c,
+		})
+		.collect()
+}
+
+async fn create_car(
+	client: &client::WmClient,
+	card: &Card,
+	req: wm::CreateCarRequest,
+) -> Result<wm::LoadUserResponse> {
+	client.create_car(req).await?;
+	load_user_with_card(card, client).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn card() -> Card {
+		Card {
+			access_code: String::from(mock::ACCESS_CODE),
+			chip_id: String::from(mock::CHIP_ID),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn request_needs_model_region_and_name() {
+		let mut menu = NewCarMenu::new(card(), mock::USER_ID);
+		assert!(menu.request(&[]).is_err());
+
+		menu.car.visual_model = Some(wm::Cars::FD3S as u32);
+		menu.car.region_id = Some(13);
+		menu.name_buf = String::from("TOOLONGNAME");
+		assert!(menu.request(&[]).is_err());
+
+		menu.name_buf = String::from("RX 7");
+		let req = menu.request(&[]).unwrap();
+		assert_eq!(req.car.name(), "ＲＸ\u{3000}７");
+		assert_eq!(req.car.model(), wm::Cars::FD3S as u32);
+	}
+
+	#[tokio::test]
+	async fn full_garage_replaces_a_car() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		{
+			let mut state = server.state.lock().unwrap();
+			for i in 1..MAX_CARS as u32 {
+				let mut car = state.cars[0].clone();
+				car.car.car_id = Some(mock::CAR_ID + i);
+				state.cars.push(car);
+			}
+		}
+		let cars = load_user_with_card(&card(), &client).await.unwrap().cars;
+		let mut menu = NewCarMenu {
+			card: card(),
+			user_id: mock::USER_ID,
+			car: wm::Car {
+				visual_model: Some(wm::Cars::FD3S as u32),
+				region_id: Some(13),
+				..Default::default()
+			},
+			name_buf: String::from("NEW"),
+			..Default::default()
+		};
+		assert!(menu.request(&cars).is_err());
+
+		menu.deleted_car_id = Some(mock::CAR_ID);
+		let req = menu.request(&cars).unwrap();
+		let user = create_car(&client, &card(), req).await.unwrap();
+		assert_eq!(user.cars.len(), MAX_CARS);
+		assert!(user.cars.iter().all(|car| car.car_id() != mock::CAR_ID));
+	}
+
+	#[tokio::test]
+	async fn create_car_consumes_ticket() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		server.state.lock().unwrap().users[0]
+			.car_tickets
+			.push(wm::UserItem {
+				category: wm::ItemCategory::CatCarTicketFree.into(),
+				item_id: FULL_TUNE_TICKET,
+				user_item_id: Some(1),
+				..Default::default()
+			});
+
+		let menu = NewCarMenu {
+			card: card(),
+			user_id: mock::USER_ID,
+			car: wm::Car {
+				visual_model: Some(wm::Cars::FD3S as u32),
+				region_id: Some(13),
+				..Default::default()
+			},
+			name_buf: String::from("NEW"),
+			transmission: true,
+			user_item_id: Some(1),
+			..Default::default()
+		};
+		let req = menu.request(&[]).unwrap();
+
+		let user = create_car(&client, &card(), req.clone()).await.unwrap();
+		assert_eq!(user.cars.len(), 2);
+		assert!(user.unused_car_tickets.is_empty());
+		assert!(create_car(&client, &card(), req).await.is_err());
+	}
+}
@@ -0,0 +1,310 @@
+//! The user's scratch sheet: scratching squares for items and turning to the next sheet.
+use crate::*;
+use anyhow::Result;
+use eframe::egui;
+
+const SQUARES_PER_ROW: usize = 10;
+
+pub struct ScratchMenu {
+	pub card: Card,
+	pub user_id: u32,
+	pub info: Option<wm::LoadScratchInformationResponse>,
+	/// What the last scratch handed out, shown until the next one.
+	pub earned_item: Option<wm::UserItem>,
+	/// Set once the server answers `ERR_SCRATCH_LOCKED`, until the sheet is reloaded.
+	pub locked: bool,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<Scratched>>,
+}
+
+/// The sheet after an action, with the user reloaded when an item was earned.
+pub struct Scratched {
+	info: wm::LoadScratchInformationResponse,
+	earned_item: Option<wm::UserItem>,
+	user: Option<wm::LoadUserResponse>,
+}
+
+impl ScratchMenu {
+	pub fn new(card: Card, user_id: u32) -> Self {
+		Self {
+			card,
+			user_id,
+			info: None,
+			earned_item: None,
+			locked: false,
+			error: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the reloaded user after a scratch earned an item.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+	) -> Option<wm::LoadUserResponse> {
+		let scratched = match task::poll(&mut self.pending) {
+			Some(Ok(scratched)) => {
+				self.info = Some(scratched.info);
+				self.earned_item = scratched.earned_item;
+				self.error = None;
+				scratched.user
+			}
+			Some(Err(err)) => {
+				if err.downcast_ref() == Some(&error::WmError::ScratchLocked) {
+					self.locked = true;
+					self.error = Some(String::from(
+						"The server isn't letting this card scratch right now, try again later",
+					));
+				} else {
+					self.error = Some(err.to_string());
+				}
+				None
+			}
+			None => None,
+		};
+
+		let mut action = None;
+		// Loaded as soon as the menu opens, afterwards only when asked to
+		let reload = ui
+			.add_enabled(self.pending.is_none(), egui::Button::new("Reload"))
+			.clicked();
+		if reload || (self.info.is_none() && self.pending.is_none() && self.error.is_none()) {
+			action = Some(ScratchAction::Load);
+		}
+
+		if let Some(info) = &self.info {
+			let sheet = info.scratch_sheets.get(info.current_sheet as usize);
+			ui.heading(format!(
+				"Sheet {} of {}",
+				info.current_sheet + 1,
+				info.scratch_sheets.len()
+			));
+			ui.label(format!("Scratched: {}", info.num_of_scratched));
+			if let Some(item) = &self.earned_item {
+				ui.colored_label(
+					egui::Color32::GREEN,
+					format!("Got {}", items::item_name(item.category(), item.item_id)),
+				);
+			}
+
+			ui.add_enabled_ui(self.pending.is_none() && !self.locked, |ui| {
+				if let Some(sheet) = sheet {
+					ui.label("Click a square to scratch it");
+					egui::Grid::new("ScratchGrid")
+						.num_columns(SQUARES_PER_ROW)
+						.show(ui, |ui| {
+							for (i, square) in sheet.squares.iter().enumerate() {
+								let name = items::item_name(square.category(), square.item_id);
+								if square.earned {
+									ui.add_enabled(false, egui::Button::new(name));
+								} else if ui.button("?").on_hover_text(name).clicked() {
+									action = Some(ScratchAction::Scratch(i as u32));
+								}
+								if i % SQUARES_PER_ROW == SQUARES_PER_ROW - 1 {
+									ui.end_row();
+								}
+							}
+						});
+				} else {
+					ui.label("No scratch sheet on this card");
+				}
+
+				let has_next = (info.current_sheet as usize + 1) < info.scratch_sheets.len();
+				if ui
+					.add_enabled(has_next, egui::Button::new("Turn to next sheet"))
+					.clicked()
+				{
+					action = Some(ScratchAction::Turn(info.current_sheet + 1));
+				}
+			});
+		}
+
+		if let Some(action) = action
+			&& self.pending.is_none()
+		{
+			let client = client.clone();
+			let card = self.card.clone();
+			let user_id = self.user_id;
+			let current_sheet = self.info.as_ref().map_or(0, |info| info.current_sheet);
+			if matches!(action, ScratchAction::Load) {
+				self.locked = false;
+			}
+			self.error = None;
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				let earned_item = match action {
+					ScratchAction::Load => None,
+					ScratchAction::Scratch(square) => {
+						scratch(&client, user_id, current_sheet, square).await?
+					}
+					ScratchAction::Turn(sheet) => {
+						turn_sheet(&client, user_id, sheet).await?;
+						None
+					}
+				};
+				let info = load_scratch_information(&client, user_id).await?;
+				// The item may be a car ticket, which only the user load reports
+				let user = match earned_item {
+					Some(_) => Some(load_user_with_card(&card, &client).await?),
+					None => None,
+				};
+				Ok(Scratched {
+					info,
+					earned_item,
+					user,
+				})
+			}));
+		}
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
+
+		scratched
+	}
+
+	pub fn back(&mut self) -> bool {
+		true
+	}
+}
+
+enum ScratchAction {
+	Load,
+	Scratch(u32),
+	Turn(u32),
+}
+
+async fn load_scratch_information(
+	client: &client::WmClient,
+	user_id: u32,
+) -> Result<wm::LoadScratchInformationResponse> {
+	let req = wm::LoadScratchInformationRequest { user_id };
+	client.load_scratch_information(req).await
+}
+
+async fn scratch(
+	client: &client::WmClient,
+	user_id: u32,
+	target_sheet: u32,
+	target_square: u32,
+) -> Result<Option<wm::UserItem>> {
+	let req = wm::SaveScratchSheetRequest {
+		timestamp: timestamp(),
+		target_sheet,
+		target_square,
+		user_id,
+	};
+	let res = client.save_scratch_sheet(req).await?;
+
+	Ok(res.earned_item)
+}
+
+async fn turn_sheet(client: &client::WmClient, user_id: u32, target_sheet: u32) -> Result<()> {
+	let req = wm::TurnScratchSheetRequest {
+		user_id,
+		target_sheet,
+	};
+	client.turn_scratch_sheet(req).await?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sheet() -> wm::ScratchSheet {
+		wm::ScratchSheet {
+			squares: vec![
+				wm::scratch_sheet::ScratchSquare {
+					category: wm::ItemCategory::CatBgm.into(),
+					item_id: 1,
+					earned: false,
+				},
+				wm::scratch_sheet::ScratchSquare {
+					category: wm::ItemCategory::CatCustomColor.into(),
+					item_id: 3,
+					earned: false,
+				},
+			],
+		}
+	}
+
+	#[tokio::test]
+	async fn scratch_earns_item_and_turns_sheet() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		server.state.lock().unwrap().users[0].scratch_sheets = vec![sheet(), sheet()];
+
+		let item = scratch(&client, mock::USER_ID, 0, 1)
+			.await
+			.unwrap()
+			.unwrap();
+		assert_eq!(
+			items::item_name(item.category(), item.item_id),
+			"Custom Color 3"
+		);
+		assert!(scratch(&client, mock::USER_ID, 0, 1).await.is_err());
+
+		turn_sheet(&client, mock::USER_ID, 1).await.unwrap();
+		let info = load_scratch_information(&client, mock::USER_ID)
+			.await
+			.unwrap();
+		assert_eq!(info.current_sheet, 1);
+		assert_eq!(info.num_of_scratched, 0);
+		assert!(info.scratch_sheets[0].squares[1].earned);
+		assert_eq!(info.owned_user_items, [item]);
+	}
+
+	#[tokio::test]
+	async fn reloaded_user_keeps_car_tickets_apart_from_scratch_items() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let ticket = wm::UserItem {
+			category: wm::ItemCategory::CatCarTicketFree.into(),
+			item_id: 5,
+			user_item_id: Some(1),
+			..Default::default()
+		};
+		{
+			let mut state = server.state.lock().unwrap();
+			state.users[0].scratch_sheets = vec![sheet()];
+			state.users[0].car_tickets = vec![ticket.clone()];
+		}
+		let card = Card {
+			access_code: String::from(mock::ACCESS_CODE),
+			chip_id: String::from(mock::CHIP_ID),
+			..Default::default()
+		};
+
+		let item = scratch(&client, mock::USER_ID, 0, 0)
+			.await
+			.unwrap()
+			.unwrap();
+		let user = load_user_with_card(&card, &client).await.unwrap();
+		assert_eq!(user.unused_car_tickets, [ticket]);
+		let info = load_scratch_information(&client, mock::USER_ID)
+			.await
+			.unwrap();
+		assert_eq!(info.owned_user_items, [item]);
+	}
+
+	#[tokio::test]
+	async fn locked_scratch_surfaces_error_code() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		{
+			let mut state = server.state.lock().unwrap();
+			state.users[0].scratch_sheets = vec![sheet()];
+			state.users[0].scratch_locked = true;
+		}
+
+		let err = scratch(&client, mock::USER_ID, 0, 0).await.unwrap_err();
+		assert_eq!(
+			err.downcast_ref::<error::WmError>(),
+			Some(&error::WmError::ScratchLocked)
+		);
+	}
+}
@@ -0,0 +1,303 @@
+//! Server profiles and recently used cards, kept in a JSON file between launches.
+use crate::*;
+use anyhow::{Context, Result};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT_CARDS: usize = 10;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+	pub servers: Vec<ServerProfile>,
+	/// Most recently used first.
+	pub recent_cards: Vec<RecentCard>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ServerProfile {
+	pub name: String,
+	pub url: String,
+	/// Overrides whatever port `url` ends up with.
+	pub port: Option<u16>,
+	pub verify_tls: bool,
+	pub headers: client::HeaderProfile,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecentCard {
+	/// Where the card.ini was dropped from, if it came from one.
+	pub path: Option<String>,
+	pub card: Card,
+}
+
+impl Settings {
+	pub fn path() -> Option<PathBuf> {
+		Some(config_dir()?.join("hax-6rr").join("settings.json"))
+	}
+
+	/// Falls back to empty settings when there is no file yet or it can't be read.
+	pub fn load() -> Self {
+		match Self::path() {
+			Some(path) if path.exists() => Self::load_from(&path).unwrap_or_else(|err| {
+				eprintln!("{err}");
+				Self::default()
+			}),
+			_ => Self::default(),
+		}
+	}
+
+	pub fn load_from(path: &Path) -> Result<Self> {
+		let json = std::fs::read_to_string(path)
+			.with_context(|| format!("Can't read {}", path.display()))?;
+		Ok(serde_json::from_str(&json)?)
+	}
+
+	pub fn save(&self) -> Result<()> {
+		self.save_to(&Self::path().context("No config directory")?)
+	}
+
+	pub fn save_to(&self, path: &Path) -> Result<()> {
+		if let Some(dir) = path.parent() {
+			std::fs::create_dir_all(dir)?;
+		}
+		std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+		Ok(())
+	}
+
+	/// Moves `card` to the front, dropping older entries of the same card.
+	pub fn add_recent_card(&mut self, card: RecentCard) {
+		self.recent_cards
+			.retain(|recent| recent.card.access_code != card.card.access_code);
+		self.recent_cards.insert(0, card);
+		self.recent_cards.truncate(MAX_RECENT_CARDS);
+	}
+}
+
+impl ServerProfile {
+	pub fn client(&self) -> Result<client::WmClient> {
+		let mut endpoint = endpoint::ServerEndpoint::parse(&self.url)?;
+		if let Some(port) = self.port {
+			endpoint.set_port(port);
+		}
+
+		client::WmClient::with_config(
+			&endpoint,
+			client::ClientConfig {
+				headers: self.headers.clone(),
+				accept_invalid_certs: !self.verify_tls,
+				..Default::default()
+			},
+		)
+	}
+}
+
+impl RecentCard {
+	pub fn label(&self) -> &str {
+		self.path.as_deref().unwrap_or(&self.card.access_code)
+	}
+}
+
+/// `$XDG_CONFIG_HOME`, then `%APPDATA%`, then `~/.config`.
+fn config_dir() -> Option<PathBuf> {
+	["XDG_CONFIG_HOME", "APPDATA"]
+		.iter()
+		.filter_map(std::env::var_os)
+		.find(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| Some(PathBuf::from(std::env::var_os("HOME")?).join(".config")))
+}
+
+/// Buffers for adding a server profile from the start screen.
+pub struct ProfileForm {
+	pub name: String,
+	pub url: String,
+	pub port: String,
+	pub verify_tls: bool,
+	pub user_agent: String,
+	pub revision: String,
+}
+
+impl Default for ProfileForm {
+	fn default() -> Self {
+		let headers = client::HeaderProfile::default();
+		Self {
+			name: String::new(),
+			url: String::new(),
+			port: String::new(),
+			verify_tls: false,
+			user_agent: headers.user_agent,
+			revision: headers.revision.to_string(),
+		}
+	}
+}
+
+impl ProfileForm {
+	pub fn profile(&self) -> Option<ServerProfile> {
+		if self.name.is_empty() || endpoint::ServerEndpoint::parse(&self.url).is_err() {
+			return None;
+		}
+		let port = match self.port.as_str() {
+			"" => None,
+			port => Some(port.parse().ok()?),
+		};
+
+		Some(ServerProfile {
+			name: self.name.clone(),
+			url: self.url.clone(),
+			port,
+			verify_tls: self.verify_tls,
+			headers: client::HeaderProfile {
+				user_agent: self.user_agent.clone(),
+				revision: self.revision.parse().ok()?,
+			},
+		})
+	}
+
+	/// Returns the new profile once "Save profile" is clicked with valid input.
+	pub fn show(&mut self, ui: &mut egui::Ui) -> Option<ServerProfile> {
+		let mut profile = None;
+
+		egui::CollapsingHeader::new("New server profile").show(ui, |ui| {
+			egui::Grid::new("ProfileGrid")
+				.num_columns(2)
+				.show(ui, |ui| {
+					ui.label("Name");
+					ui.text_edit_singleline(&mut self.name);
+					ui.end_row();
+
+					ui.label("Server URL");
+					ui.text_edit_singleline(&mut self.url);
+					ui.end_row();
+
+					ui.label("Port");
+					ui.add(egui::TextEdit::singleline(&mut self.port).hint_text("Default"));
+					ui.end_row();
+
+					ui.label("Verify TLS certificate");
+					ui.add(egui::Checkbox::without_text(&mut self.verify_tls));
+					ui.end_row();
+
+					ui.label("User agent");
+					ui.text_edit_singleline(&mut self.user_agent);
+					ui.end_row();
+
+					ui.label("Revision");
+					ui.text_edit_singleline(&mut self.revision);
+					ui.end_row();
+				});
+
+			let new_profile = self.profile();
+			if ui
+				.add_enabled(new_profile.is_some(), egui::Button::new("Save profile"))
+				.clicked()
+			{
+				profile = new_profile;
+				*self = Self::default();
+			}
+		});
+
+		profile
+	}
+}
+
+/// Lists saved profiles, returning the one clicked. Removing one saves right away.
+pub fn select_server_profile(
+	ui: &mut egui::Ui,
+	settings: &mut Settings,
+) -> Result<Option<ServerProfile>> {
+	let mut selected = None;
+	let mut removed = None;
+
+	for (i, profile) in settings.servers.iter().enumerate() {
+		ui.horizontal(|ui| {
+			if ui
+				.button(format!("{} ({})", profile.name, profile.url))
+				.clicked()
+			{
+				selected = Some(profile.clone());
+			}
+			if ui.small_button("Remove").clicked() {
+				removed = Some(i);
+			}
+		});
+	}
+
+	if let Some(i) = removed {
+		settings.servers.remove(i);
+		settings.save()?;
+	}
+
+	Ok(selected)
+}
+
+pub fn select_recent_card(ui: &mut egui::Ui, settings: &Settings) -> Option<RecentCard> {
+	if !settings.recent_cards.is_empty() {
+		ui.label("Recent cards");
+	}
+	for recent in settings.recent_cards.iter() {
+		if ui.button(recent.label()).clicked() {
+			return Some(recent.clone());
+		}
+	}
+
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn recent_card(access_code: &str) -> RecentCard {
+		RecentCard {
+			path: None,
+			card: Card {
+				access_code: String::from(access_code),
+				..Default::default()
+			},
+		}
+	}
+
+	#[test]
+	fn settings_round_trip() {
+		let path = std::env::temp_dir()
+			.join(format!("hax-6rr-{}-settings", std::process::id()))
+			.join("settings.json");
+		let mut settings = Settings::default();
+		settings.servers.push(ServerProfile {
+			name: String::from("Local"),
+			url: String::from("http://127.0.0.1"),
+			port: Some(9002),
+			verify_tls: false,
+			headers: client::HeaderProfile::default(),
+		});
+		settings.add_recent_card(recent_card(mock::ACCESS_CODE));
+
+		settings.save_to(&path).unwrap();
+		let loaded = Settings::load_from(&path);
+		_ = std::fs::remove_dir_all(path.parent().unwrap());
+
+		assert_eq!(loaded.unwrap(), settings);
+	}
+
+	#[test]
+	fn recent_cards_are_deduplicated_and_capped() {
+		let mut settings = Settings::default();
+		for i in 0..MAX_RECENT_CARDS + 5 {
+			settings.add_recent_card(recent_card(&i.to_string()));
+		}
+		settings.add_recent_card(recent_card("3"));
+
+		assert_eq!(settings.recent_cards.len(), MAX_RECENT_CARDS);
+		assert_eq!(settings.recent_cards[0].card.access_code, "3");
+		assert_eq!(
+			settings
+				.recent_cards
+				.iter()
+				.filter(|recent| recent.card.access_code == "3")
+				.count(),
+			1
+		);
+	}
+}
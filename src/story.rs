@@ -0,0 +1,286 @@
+//! Story mode progress: which episodes are cleared or lost, streaks and totals.
+use crate::*;
+use anyhow::Result;
+use eframe::egui;
+
+/// Episodes tracked by `st_clear_bits`, one bit each.
+pub const EPISODES_PER_DIV: usize = 32;
+const EPISODES_PER_ROW: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Episode {
+	Unplayed,
+	Cleared,
+	Lost,
+}
+
+impl Episode {
+	fn label(self) -> &'static str {
+		match self {
+			Episode::Unplayed => "-",
+			Episode::Cleared => "Cleared",
+			Episode::Lost => "Lost",
+		}
+	}
+
+	/// What clicking the cell in the grid turns it into.
+	fn next(self) -> Self {
+		match self {
+			Episode::Unplayed => Episode::Cleared,
+			Episode::Cleared => Episode::Lost,
+			Episode::Lost => Episode::Unplayed,
+		}
+	}
+}
+
+/// The story fields of a car, as far as `StoryResult` can write them back.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoryProgress {
+	pub play_count: u32,
+	/// Episodes cleared in the division being played.
+	pub clear_bits: u32,
+	/// Divisions cleared in full.
+	pub clear_div_count: u32,
+	pub clear_count: u32,
+	/// Lost episodes, the low bits line up with `clear_bits`.
+	pub lose_bits: u64,
+	pub consecutive_wins: u32,
+	pub completed_100_episodes: bool,
+}
+
+impl StoryProgress {
+	pub fn new(car: &wm::LoadCarResponse) -> Self {
+		Self {
+			play_count: car.st_play_count,
+			clear_bits: car.st_clear_bits,
+			clear_div_count: car.st_clear_div_count,
+			clear_count: car.st_clear_count,
+			lose_bits: car.st_lose_bits,
+			consecutive_wins: car.st_consecutive_wins,
+			completed_100_episodes: car.st_completed_100_episodes,
+		}
+	}
+
+	pub fn episode(&self, episode: usize) -> Episode {
+		if self.clear_bits & (1 << episode) != 0 {
+			Episode::Cleared
+		} else if self.lose_bits & (1 << episode) != 0 {
+			Episode::Lost
+		} else {
+			Episode::Unplayed
+		}
+	}
+
+	pub fn set_episode(&mut self, episode: usize, state: Episode) {
+		self.clear_bits &= !(1 << episode);
+		self.lose_bits &= !(1 << episode);
+		match state {
+			Episode::Unplayed => {}
+			Episode::Cleared => self.clear_bits |= 1 << episode,
+			Episode::Lost => self.lose_bits |= 1 << episode,
+		}
+	}
+
+	pub fn result(&self, tuning_point: u32) -> wm::save_game_result_request::StoryResult {
+		wm::save_game_result_request::StoryResult {
+			st_play_count: self.play_count,
+			st_played_story: 0,
+			tuning_point: Some(tuning_point),
+			st_clear_bits: Some(self.clear_bits),
+			st_clear_div_count: Some(self.clear_div_count),
+			st_clear_count: Some(self.clear_count),
+			st_lose_bits: Some(self.lose_bits),
+			st_consecutive_wins: Some(self.consecutive_wins),
+			st_completed_100_episodes: Some(self.completed_100_episodes),
+			..Default::default()
+		}
+	}
+}
+
+pub struct StoryMenu {
+	pub progress: StoryProgress,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::LoadCarResponse>>,
+}
+
+impl StoryMenu {
+	pub fn new(loaded: &wm::LoadCarResponse) -> Self {
+		Self {
+			progress: StoryProgress::new(loaded),
+			error: None,
+			pending: None,
+		}
+	}
+
+	/// Returns the car as the server has it after the progress was saved.
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		loaded: &wm::LoadCarResponse,
+	) -> Option<wm::LoadCarResponse> {
+		let saved = match task::poll(&mut self.pending) {
+			Some(Ok(car)) => {
+				self.progress = StoryProgress::new(&car);
+				self.error = None;
+				Some(car)
+			}
+			Some(Err(err)) => {
+				self.error = Some(err.to_string());
+				None
+			}
+			None => None,
+		};
+
+		let unchanged = self.progress == StoryProgress::new(loaded);
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
+			ui.horizontal(|ui| {
+				if ui
+					.add_enabled(!unchanged, egui::Button::new("Save"))
+					.clicked()
+				{
+					let client = client.clone();
+					let loaded = loaded.clone();
+					let progress = self.progress.clone();
+					self.error = None;
+					self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+						save_story(&client, &loaded, &progress).await?;
+						load_car(loaded.car.car_id(), &client).await
+					}));
+				}
+				if ui
+					.add_enabled(!unchanged, egui::Button::new("Revert"))
+					.clicked()
+				{
+					self.progress = StoryProgress::new(loaded);
+				}
+				if self.pending.is_some() {
+					ui.spinner();
+				}
+			});
+			show_error(ui, &self.error);
+
+			ui.label("Click an episode to cycle it between unplayed, cleared and lost");
+			egui::Grid::new("StoryEpisodeGrid")
+				.num_columns(EPISODES_PER_ROW)
+				.show(ui, |ui| {
+					for episode in 0..EPISODES_PER_DIV {
+						let state = self.progress.episode(episode);
+						let text = format!("{} {}", episode + 1, state.label());
+						let text = match state {
+							Episode::Unplayed => egui::RichText::new(text),
+							Episode::Cleared => {
+								egui::RichText::new(text).color(egui::Color32::GREEN)
+							}
+							Episode::Lost => egui::RichText::new(text).color(egui::Color32::RED),
+						};
+						if ui.button(text).clicked() {
+							self.progress.set_episode(episode, state.next());
+						}
+						if episode % EPISODES_PER_ROW == EPISODES_PER_ROW - 1 {
+							ui.end_row();
+						}
+					}
+				});
+
+			egui::Grid::new("StoryGrid").num_columns(2).show(ui, |ui| {
+				ui.label("Play Count");
+				ui.add(egui::DragValue::new(&mut self.progress.play_count));
+				ui.end_row();
+
+				ui.label("Cleared Divisions");
+				ui.add(egui::DragValue::new(&mut self.progress.clear_div_count));
+				ui.end_row();
+
+				ui.label("Total Clears");
+				ui.add(egui::DragValue::new(&mut self.progress.clear_count));
+				ui.end_row();
+
+				ui.label("Win Streak");
+				ui.add(egui::DragValue::new(&mut self.progress.consecutive_wins));
+				ui.end_row();
+
+				// StoryResult has no field for it, so it can only be shown
+				ui.label("Best Win Streak");
+				ui.label(loaded.st_consecutive_wins_max.to_string());
+				ui.end_row();
+
+				ui.label("Completed 100 Episodes");
+				ui.add(egui::Checkbox::without_text(
+					&mut self.progress.completed_100_episodes,
+				));
+				ui.end_row();
+			});
+		});
+
+		saved
+	}
+
+	pub fn back(&mut self) -> bool {
+		true
+	}
+}
+
+/// Sends the car as loaded so unsaved edits from the other menus don't go along.
+async fn save_story(
+	client: &client::WmClient,
+	loaded: &wm::LoadCarResponse,
+	progress: &StoryProgress,
+) -> Result<wm::SaveGameResultResponse> {
+	let req = wm::SaveGameResultRequest {
+		car_id: loaded.car.car_id(),
+		game_mode: wm::GameMode::ModeStory.into(),
+		played_at: loaded.car.last_played_at(),
+		play_count: loaded.play_count,
+		car: Some(loaded.car.clone()),
+		setting: Some(loaded.setting.clone()),
+		odometer: Some(loaded.odometer),
+		earned_custom_color: Some(loaded.earned_custom_color),
+		retired: false,
+		st_result: Some(progress.result(loaded.tuning_point)),
+		..Default::default()
+	};
+
+	client.save_game_result(req).await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn episodes_map_to_bits() {
+		let mut progress = StoryProgress {
+			clear_bits: 0b101,
+			lose_bits: 0b010 | 1 << 40,
+			..Default::default()
+		};
+		assert_eq!(progress.episode(0), Episode::Cleared);
+		assert_eq!(progress.episode(1), Episode::Lost);
+		assert_eq!(progress.episode(3), Episode::Unplayed);
+
+		progress.set_episode(0, Episode::Lost);
+		progress.set_episode(1, Episode::Cleared);
+		progress.set_episode(2, Episode::Unplayed);
+		assert_eq!(progress.clear_bits, 0b010);
+		assert_eq!(progress.lose_bits, 0b001 | 1 << 40);
+	}
+
+	#[tokio::test]
+	async fn save_story_writes_progress() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let loaded = load_car(mock::CAR_ID, &client).await.unwrap();
+		let mut progress = StoryProgress::new(&loaded);
+		progress.set_episode(4, Episode::Cleared);
+		progress.consecutive_wins = 12;
+		progress.completed_100_episodes = true;
+
+		save_story(&client, &loaded, &progress).await.unwrap();
+
+		let car = load_car(mock::CAR_ID, &client).await.unwrap();
+		assert_eq!(StoryProgress::new(&car), progress);
+		assert_eq!(car.odometer, loaded.odometer);
+	}
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use eframe::egui;
+use std::future::Future;
+use tokio::sync::oneshot;
+
+/// A request running on the tokio runtime whose result is picked up by the UI each frame.
+pub struct Task<T> {
+	rx: oneshot::Receiver<Result<T>>,
+}
+
+impl<T: Send + 'static> Task<T> {
+	pub fn spawn<F>(runtime: &tokio::runtime::Runtime, ctx: &egui::Context, future: F) -> Self
+	where
+		F: Future<Output = Result<T>> + Send + 'static,
+	{
+		let (tx, rx) = oneshot::channel();
+		let ctx = ctx.clone();
+		runtime.spawn(async move {
+			_ = tx.send(future.await);
+			// Wake the UI up so the result is shown without waiting for input
+			ctx.request_repaint();
+		});
+
+		Self { rx }
+	}
+
+	fn try_take(&mut self) -> Option<Result<T>> {
+		match self.rx.try_recv() {
+			Ok(result) => Some(result),
+			Err(oneshot::error::TryRecvError::Empty) => None,
+			Err(oneshot::error::TryRecvError::Closed) => {
+				Some(Err(anyhow::format_err!("Request was cancelled")))
+			}
+		}
+	}
+}
+
+/// Takes the result out of `task` once it is done, leaving `None` behind.
+pub fn poll<T: Send + 'static>(task: &mut Option<Task<T>>) -> Option<Result<T>> {
+	let result = task.as_mut()?.try_take();
+	if result.is_some() {
+		*task = None;
+	}
+	result
+}
@@ -0,0 +1,633 @@
+//! Time attack records: the car's personal best on every course and how it stacks up.
+use crate::*;
+use anyhow::{bail, Context, Result};
+use eframe::egui;
+
+/// Every course `RankingType` has a time attack ranking for, in the game's order.
+pub const COURSES: [wm::RankingType; 25] = [
+	wm::RankingType::RankingTaC1in,
+	wm::RankingType::RankingTaC1out,
+	wm::RankingType::RankingTaRingleft,
+	wm::RankingType::RankingTaRingright,
+	wm::RankingType::RankingTaSubtokyo34,
+	wm::RankingType::RankingTaSubtokyo5,
+	wm::RankingType::RankingTaWanganeast,
+	wm::RankingType::RankingTaWanganwest,
+	wm::RankingType::RankingTaK1Down,
+	wm::RankingType::RankingTaK1Up,
+	wm::RankingType::RankingTaYaesuin,
+	wm::RankingType::RankingTaYaesuout,
+	wm::RankingType::RankingTaYokohamain,
+	wm::RankingType::RankingTaYokohamaout,
+	wm::RankingType::RankingTaNagoya,
+	wm::RankingType::RankingTaOsaka,
+	wm::RankingType::RankingTaKobe,
+	wm::RankingType::RankingTaFukuoka,
+	wm::RankingType::RankingTaHakonefor,
+	wm::RankingType::RankingTaHakoneback,
+	wm::RankingType::RankingTaTurnpikeUp,
+	wm::RankingType::RankingTaTurnpikeDown,
+	wm::RankingType::RankingTaTokyoall,
+	wm::RankingType::RankingTaKanagawaall,
+	wm::RankingType::RankingTaHiroshima,
+];
+
+pub fn course_name(course: wm::RankingType) -> &'static str {
+	match course {
+		wm::RankingType::RankingTaC1in => "C1 Inner",
+		wm::RankingType::RankingTaC1out => "C1 Outer",
+		wm::RankingType::RankingTaRingleft => "New Belt Line Left",
+		wm::RankingType::RankingTaRingright => "New Belt Line Right",
+		wm::RankingType::RankingTaSubtokyo34 => "Subcenter Line 3/4",
+		wm::RankingType::RankingTaSubtokyo5 => "Subcenter Line 5",
+		wm::RankingType::RankingTaWanganeast => "Wangan Line East",
+		wm::RankingType::RankingTaWanganwest => "Wangan Line West",
+		wm::RankingType::RankingTaK1Down => "Yokohane Line Down",
+		wm::RankingType::RankingTaK1Up => "Yokohane Line Up",
+		wm::RankingType::RankingTaYaesuin => "Yaesu Inner",
+		wm::RankingType::RankingTaYaesuout => "Yaesu Outer",
+		wm::RankingType::RankingTaYokohamain => "Minatomirai Inner",
+		wm::RankingType::RankingTaYokohamaout => "Minatomirai Outer",
+		wm::RankingType::RankingTaNagoya => "Nagoya",
+		wm::RankingType::RankingTaOsaka => "Osaka",
+		wm::RankingType::RankingTaKobe => "Kobe",
+		wm::RankingType::RankingTaFukuoka => "Fukuoka",
+		wm::RankingType::RankingTaHakonefor => "Hakone Forward",
+		wm::RankingType::RankingTaHakoneback => "Hakone Backward",
+		wm::RankingType::RankingTaTurnpikeUp => "Turnpike Up",
+		wm::RankingType::RankingTaTurnpikeDown => "Turnpike Down",
+		wm::RankingType::RankingTaTokyoall => "Tokyo All",
+		wm::RankingType::RankingTaKanagawaall => "Kanagawa All",
+		wm::RankingType::RankingTaHiroshima => "Hiroshima",
+		wm::RankingType::RankingVsStar | wm::RankingType::RankingGhostTrophy => "",
+	}
+}
+
+/// Sections 1 to 4 are always sent, the rest only on courses that have them.
+const MAX_SECTIONS: usize = 7;
+const REQUIRED_SECTIONS: usize = 4;
+
+/// Times come in milliseconds and are shown the way the cabinet does, 2'05"123.
+pub fn format_time(time: u32) -> String {
+	format!(
+		"{}'{:02}\"{:03}",
+		time / 60_000,
+		time / 1000 % 60,
+		time % 1000
+	)
+}
+
+/// Takes either plain milliseconds or the cabinet's 2'05"123.
+pub fn parse_time(buf: &str) -> Result<u32> {
+	let buf = buf.trim();
+	if let Ok(time) = buf.parse() {
+		return Ok(time);
+	}
+
+	let parsed = buf.split_once('\'').and_then(|(minutes, rest)| {
+		let (seconds, millis) = rest.split_once('"')?;
+		let minutes = minutes.parse::<u32>().ok()?;
+		if seconds.len() != 2 || millis.len() != 3 {
+			return None;
+		}
+		let seconds = seconds
+			.parse::<u32>()
+			.ok()
+			.filter(|&seconds| seconds < 60)?;
+		let millis = millis.parse::<u32>().ok()?;
+		Some(minutes * 60_000 + seconds * 1000 + millis)
+	});
+	parsed.with_context(|| format!("{buf} isn't a time, use milliseconds or 2'05\"123"))
+}
+
+/// A time attack result for `course`, checking the sections add up to the total.
+pub fn ta_result(
+	course: wm::RankingType,
+	time: u32,
+	is_morning: bool,
+	sections: &[u32],
+) -> Result<wm::save_game_result_request::TimeAttackResult> {
+	if !COURSES.contains(&course) {
+		bail!("{} isn't a time attack course", course.as_str_name());
+	}
+	if sections.len() < REQUIRED_SECTIONS || sections.len() > MAX_SECTIONS {
+		bail!("Enter between {REQUIRED_SECTIONS} and {MAX_SECTIONS} section times");
+	}
+	let sum = sections.iter().sum::<u32>();
+	if sum != time {
+		bail!(
+			"Sections add up to {} but the total is {}",
+			format_time(sum),
+			format_time(time)
+		);
+	}
+
+	Ok(wm::save_game_result_request::TimeAttackResult {
+		time,
+		course: course as u32,
+		is_morning,
+		section_1_time: sections[0],
+		section_2_time: sections[1],
+		section_3_time: sections[2],
+		section_4_time: sections[3],
+		section_5_time: sections.get(4).copied(),
+		section_6_time: sections.get(5).copied(),
+		section_7_time: sections.get(6).copied(),
+		..Default::default()
+	})
+}
+
+pub async fn read_csv(
+	path: &std::path::Path,
+) -> Result<Vec<wm::save_game_result_request::TimeAttackResult>> {
+	let text = tokio::fs::read_to_string(path)
+		.await
+		.with_context(|| format!("Couldn't read {}", path.display()))?;
+	parse_csv(&text)
+}
+
+/// Results from a CSV with `course,time,is_morning,section_1,...` rows.
+///
+/// Courses can be given by number or `RankingType` name, a header row starting with `course`
+/// and blank lines are skipped.
+pub fn parse_csv(text: &str) -> Result<Vec<wm::save_game_result_request::TimeAttackResult>> {
+	let mut results = Vec::new();
+	for (i, line) in text.lines().enumerate() {
+		let fields = line.split(',').map(str::trim).collect::<Vec<_>>();
+		if fields.iter().all(|field| field.is_empty()) || fields[0].eq_ignore_ascii_case("course") {
+			continue;
+		}
+		let result = parse_row(&fields).with_context(|| format!("Line {}", i + 1))?;
+		results.push(result);
+	}
+
+	Ok(results)
+}
+
+fn parse_row(fields: &[&str]) -> Result<wm::save_game_result_request::TimeAttackResult> {
+	let [course, time, is_morning, sections @ ..] = fields else {
+		bail!("Expected course, time, morning and the section times");
+	};
+	let course = match course.parse::<i32>() {
+		Ok(course) => wm::RankingType::try_from(course).ok(),
+		Err(_) => wm::RankingType::from_str_name(course),
+	}
+	.with_context(|| format!("Unknown course {course}"))?;
+	let is_morning = match is_morning.to_ascii_lowercase().as_str() {
+		"" | "0" | "false" | "no" => false,
+		"1" | "true" | "yes" => true,
+		_ => bail!("Morning should be true or false, not {is_morning}"),
+	};
+	let sections = sections
+		.iter()
+		.filter(|section| !section.is_empty())
+		.map(|section| parse_time(section))
+		.collect::<Result<Vec<_>>>()?;
+
+	ta_result(course, parse_time(time)?, is_morning, &sections)
+}
+
+/// What's typed into the form for writing a single record.
+#[derive(Default)]
+pub struct TaForm {
+	pub course: wm::RankingType,
+	pub time_buf: String,
+	pub is_morning: bool,
+	pub section_bufs: [String; MAX_SECTIONS],
+}
+
+impl TaForm {
+	pub fn result(&self) -> Result<wm::save_game_result_request::TimeAttackResult> {
+		let sections = self
+			.section_bufs
+			.iter()
+			.filter(|buf| !buf.trim().is_empty())
+			.map(|buf| parse_time(buf))
+			.collect::<Result<Vec<_>>>()?;
+		ta_result(
+			self.course,
+			parse_time(&self.time_buf)?,
+			self.is_morning,
+			&sections,
+		)
+	}
+}
+
+/// How far `time` is off `target`, with the sign in front, -0'01"234.
+pub fn format_gap(time: u32, target: u32) -> String {
+	if time >= target {
+		format!("+{}", format_time(time - target))
+	} else {
+		format!("-{}", format_time(target - time))
+	}
+}
+
+/// The personal best's section times, skipping the sections a course doesn't have, which
+/// come as nothing or zero.
+pub fn pb_sections(record: &wm::LoadTimeAttackRecordResponse) -> Vec<u32> {
+	[
+		record.pb_section_1_time,
+		record.pb_section_2_time,
+		record.pb_section_3_time,
+		record.pb_section_4_time,
+		record.pb_section_5_time,
+		record.pb_section_6_time,
+		record.pb_section_7_time,
+	]
+	.into_iter()
+	.flatten()
+	.filter(|&time| time > 0)
+	.collect()
+}
+
+/// Each section scaled to what it would take at the pace of `target` overall.
+///
+/// Only the top time is ranked, not its sections, so this assumes it was driven with the
+/// same split between sections as the personal best.
+pub fn target_sections(sections: &[u32], target: u32) -> Vec<u32> {
+	let total = sections.iter().map(|&time| time as u64).sum::<u64>();
+	if total == 0 {
+		return vec![0; sections.len()];
+	}
+	sections
+		.iter()
+		.map(|&time| (time as u64 * target as u64 / total) as u32)
+		.collect()
+}
+
+#[derive(Default)]
+pub struct TimeAttackMenu {
+	pub records: Vec<(wm::RankingType, wm::LoadTimeAttackRecordResponse)>,
+	pub selected: Option<wm::RankingType>,
+	pub form: TaForm,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<Vec<(wm::RankingType, wm::LoadTimeAttackRecordResponse)>>>,
+}
+
+impl TimeAttackMenu {
+	pub fn update(
+		&mut self,
+		ui: &mut egui::Ui,
+		runtime: &tokio::runtime::Runtime,
+		client: &client::WmClient,
+		loaded: &wm::LoadCarResponse,
+	) {
+		match task::poll(&mut self.pending) {
+			Some(Ok(records)) => {
+				self.records = records;
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(format!("{err:#}")),
+			None => {}
+		}
+
+		// Every course is queried as soon as the menu opens, afterwards only when asked to
+		let reload = ui
+			.add_enabled(self.pending.is_none(), egui::Button::new("Reload"))
+			.clicked();
+		if reload || (self.records.is_empty() && self.pending.is_none() && self.error.is_none()) {
+			let client = client.clone();
+			let car = loaded.car.clone();
+			self.error = None;
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				load_all_records(&client, &car).await
+			}));
+		}
+
+		if self.pending.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
+
+		let mut save = None;
+		let mut import = None;
+		egui::CollapsingHeader::new("Write Records").show(ui, |ui| {
+			let result = self.form.result();
+			ui.add_enabled_ui(self.pending.is_none(), |ui| {
+				form_grid(ui, &mut self.form);
+				if ui
+					.add_enabled(result.is_ok(), egui::Button::new("Save Record"))
+					.clicked()
+				{
+					save = result.as_ref().ok().map(|result| vec![result.clone()]);
+				}
+			});
+			if let Err(err) = &result {
+				ui.colored_label(egui::Color32::RED, err.to_string());
+			}
+
+			ui.label("Or drop a CSV of course,time,morning,section 1,... rows onto the window");
+			let dropped = ui.ctx().input(|i| i.raw.dropped_files.clone());
+			import = dropped.into_iter().find_map(|file| file.path);
+		});
+
+		if (save.is_some() || import.is_some()) && self.pending.is_none() {
+			let client = client.clone();
+			let loaded = loaded.clone();
+			self.error = None;
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				let results = match import {
+					Some(path) => read_csv(&path).await?,
+					None => save.unwrap_or_default(),
+				};
+				save_ta_results(&client, &loaded, results).await?;
+				load_all_records(&client, &loaded.car).await
+			}));
+		}
+
+		egui::ScrollArea::vertical().show(ui, |ui| {
+			if let Some(course) = self.selected {
+				if let Some((_, record)) = self.records.iter().find(|(c, _)| *c == course) {
+					ui.heading(course_name(course));
+					sections_grid(ui, record);
+				}
+				ui.separator();
+			}
+
+			ui.label("Pick a course to compare its sections against the top time");
+			egui::Grid::new("TimeAttackGrid")
+				.num_columns(5)
+				.striped(true)
+				.show(ui, |ui| {
+					ui.label("Course");
+					ui.label("Personal Best");
+					ui.label("Rank");
+					ui.label("Model Rank");
+					ui.label("Top Time");
+					ui.end_row();
+
+					for (course, record) in self.records.iter() {
+						if ui
+							.selectable_label(self.selected == Some(*course), course_name(*course))
+							.clicked()
+						{
+							self.selected = Some(*course);
+						}
+						ui.label(
+							record
+								.personal_best_time
+								.map_or(String::from("-"), format_time),
+						);
+						ui.label(rank(record.personal_best_time, &record.whole_ranking));
+						ui.label(rank(record.personal_best_time, &record.model_ranking));
+						ui.label(
+							record
+								.whole_ranking
+								.first()
+								.map_or(String::from("-"), |&time| format_time(time)),
+						);
+						ui.end_row();
+					}
+				});
+		});
+	}
+
+	pub fn back(&mut self) -> bool {
+		if self.selected.is_some() {
+			self.selected = None;
+			false
+		} else {
+			true
+		}
+	}
+}
+
+fn form_grid(ui: &mut egui::Ui, form: &mut TaForm) {
+	egui::Grid::new("TimeAttackFormGrid")
+		.num_columns(2)
+		.show(ui, |ui| {
+			ui.label("Course");
+			egui::ComboBox::from_id_source("TimeAttackCourseComboBox")
+				.selected_text(course_name(form.course))
+				.show_ui(ui, |ui| {
+					for course in COURSES {
+						ui.selectable_value(&mut form.course, course, course_name(course));
+					}
+				});
+			ui.end_row();
+
+			ui.label("Time");
+			ui.add(egui::TextEdit::singleline(&mut form.time_buf).hint_text("2'05\"123"));
+			ui.end_row();
+
+			ui.label("Morning");
+			ui.add(egui::Checkbox::without_text(&mut form.is_morning));
+			ui.end_row();
+
+			for (i, buf) in form.section_bufs.iter_mut().enumerate() {
+				ui.label(format!("Section {}", i + 1));
+				let hint = if i < REQUIRED_SECTIONS { "" } else { "None" };
+				ui.add(egui::TextEdit::singleline(buf).hint_text(hint));
+				ui.end_row();
+			}
+		});
+}
+
+fn sections_grid(ui: &mut egui::Ui, record: &wm::LoadTimeAttackRecordResponse) {
+	let Some(pb) = record.personal_best_time else {
+		ui.label("No personal best on this course");
+		return;
+	};
+	let top = record.whole_ranking.first().copied().unwrap_or(pb);
+	let sections = pb_sections(record);
+	let targets = target_sections(&sections, top);
+
+	egui::Grid::new("TimeAttackSectionGrid")
+		.num_columns(4)
+		.striped(true)
+		.show(ui, |ui| {
+			ui.label("Section");
+			ui.label("Time");
+			ui.label("At Top Pace");
+			ui.label("Gap");
+			ui.end_row();
+
+			for (i, (&time, &target)) in sections.iter().zip(targets.iter()).enumerate() {
+				ui.label(format!("{}", i + 1));
+				ui.label(format_time(time));
+				ui.label(format_time(target));
+				ui.label(format_gap(time, target));
+				ui.end_row();
+			}
+
+			ui.label("Total");
+			ui.label(format_time(pb));
+			ui.label(format_time(top));
+			ui.label(format_gap(pb, top));
+			ui.end_row();
+		});
+}
+
+/// Where `time` places in `ranking`, which the server sends fastest first.
+fn rank(time: Option<u32>, ranking: &[u32]) -> String {
+	match time {
+		Some(time) => format!(
+			"{} / {}",
+			ranking.iter().filter(|&&ranked| ranked < time).count() + 1,
+			ranking.len()
+		),
+		None => format!("- / {}", ranking.len()),
+	}
+}
+
+async fn load_all_records(
+	client: &client::WmClient,
+	car: &wm::Car,
+) -> Result<Vec<(wm::RankingType, wm::LoadTimeAttackRecordResponse)>> {
+	let mut records = Vec::with_capacity(COURSES.len());
+	for course in COURSES {
+		let req = wm::LoadTimeAttackRecordRequest {
+			car_id: Some(car.car_id()),
+			model: car.model(),
+			course: course as u32,
+		};
+		records.push((course, client.load_time_attack_record(req).await?));
+	}
+
+	Ok(records)
+}
+
+/// Each result is its own play, sent with the car as loaded like the other progress saves.
+async fn save_ta_results(
+	client: &client::WmClient,
+	loaded: &wm::LoadCarResponse,
+	results: Vec<wm::save_game_result_request::TimeAttackResult>,
+) -> Result<()> {
+	for ta_result in results {
+		let req = wm::SaveGameResultRequest {
+			car_id: loaded.car.car_id(),
+			game_mode: wm::GameMode::ModeTimeAttack.into(),
+			played_at: loaded.car.last_played_at(),
+			play_count: loaded.play_count,
+			car: Some(loaded.car.clone()),
+			setting: Some(loaded.setting.clone()),
+			odometer: Some(loaded.odometer),
+			earned_custom_color: Some(loaded.earned_custom_color),
+			retired: false,
+			ta_result: Some(ta_result),
+			..Default::default()
+		};
+		client.save_game_result(req).await?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn times_and_gaps_are_formatted() {
+		assert_eq!(format_time(125_123), "2'05\"123");
+		assert_eq!(format_gap(100_000, 98_766), "+0'01\"234");
+		assert_eq!(format_gap(98_766, 100_000), "-0'01\"234");
+		assert_eq!(target_sections(&[30_000, 70_000], 90_000), [27_000, 63_000]);
+		assert_eq!(rank(Some(50), &[40, 50, 60]), "2 / 3");
+	}
+
+	#[test]
+	fn csv_rows_are_checked() {
+		let results = parse_csv(
+			"course,time,morning,s1,s2,s3,s4\n\
+			 6,1'00\"000,false,15000,15000,15000,15000\n\
+			 \n\
+			 RANKING_TA_C1IN,60000,true,10000,10000,10000,10000,20000\n",
+		)
+		.unwrap();
+		assert_eq!(results.len(), 2);
+		assert_eq!(
+			results[0].course,
+			wm::RankingType::RankingTaWanganeast as u32
+		);
+		assert_eq!(results[1].section_5_time, Some(20_000));
+		assert!(results[1].is_morning);
+
+		let err = parse_csv("6,60000,false,10000,10000,10000,10000").unwrap_err();
+		assert_eq!(err.to_string(), "Line 1");
+		assert!(parse_csv("100,60000,false,15000,15000,15000,15000").is_err());
+		assert!(parse_time("1'5\"000").is_err());
+	}
+
+	#[tokio::test]
+	async fn csv_is_read_from_disk() {
+		let path = std::env::temp_dir().join(format!("hax-6rr-{}-ta.csv", std::process::id()));
+		tokio::fs::write(&path, "6,60000,false,15000,15000,15000,15000\n")
+			.await
+			.unwrap();
+
+		let results = read_csv(&path).await;
+		_ = tokio::fs::remove_file(&path).await;
+		assert_eq!(results.unwrap().len(), 1);
+
+		let err = read_csv(&path).await.unwrap_err();
+		assert!(err.to_string().starts_with("Couldn't read"));
+	}
+
+	#[tokio::test]
+	async fn saved_results_become_personal_bests() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let loaded = load_car(mock::CAR_ID, &client).await.unwrap();
+		let form = TaForm {
+			course: wm::RankingType::RankingTaHakonefor,
+			time_buf: String::from("2'00\"000"),
+			section_bufs: ["30000", "30000", "30000", "30000", "", "", ""].map(String::from),
+			..Default::default()
+		};
+		let slower = ta_result(
+			form.course,
+			130_000,
+			false,
+			&[40_000, 30_000, 30_000, 30_000],
+		);
+
+		save_ta_results(
+			&client,
+			&loaded,
+			vec![form.result().unwrap(), slower.unwrap()],
+		)
+		.await
+		.unwrap();
+
+		let records = load_all_records(&client, &loaded.car).await.unwrap();
+		let (_, record) = records
+			.iter()
+			.find(|(course, _)| *course == form.course)
+			.unwrap();
+		assert_eq!(record.personal_best_time, Some(120_000));
+		assert_eq!(pb_sections(record), [30_000; 4]);
+	}
+
+	#[tokio::test]
+	async fn load_all_records_covers_every_course() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let client = server.client();
+		let course = wm::RankingType::RankingTaWanganeast as u32;
+		{
+			let mut state = server.state.lock().unwrap();
+			for (car_id, time) in [(mock::CAR_ID, 100_000), (mock::CAR_ID + 1, 90_000)] {
+				state.ta_records.push(mock::MockTaRecord {
+					car_id,
+					result: wm::save_game_result_request::TimeAttackResult {
+						time,
+						course,
+						section_1_time: time / 2,
+						section_2_time: time / 2,
+						..Default::default()
+					},
+				});
+			}
+		}
+		let car = load_car(mock::CAR_ID, &client).await.unwrap().car;
+
+		let records = load_all_records(&client, &car).await.unwrap();
+		assert_eq!(records.len(), COURSES.len());
+		let (_, record) = records.iter().find(|(c, _)| *c as u32 == course).unwrap();
+		assert_eq!(record.personal_best_time, Some(100_000));
+		assert_eq!(pb_sections(record), [50_000, 50_000]);
+		assert_eq!(record.whole_ranking, [90_000, 100_000]);
+		// The other car isn't in the mock's garage, so its model is unknown
+		assert_eq!(record.model_ranking, [100_000]);
+	}
+}
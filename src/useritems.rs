@@ -1,11 +1,16 @@
 use crate::*;
 use anyhow::Result;
 use eframe::egui;
-use url::Url;
 
 pub struct UserItems {
+	pub user_id: u32,
 	pub selected_category: Option<wm::ItemCategory>,
 	pub new_item_buf: Option<u32>,
+	/// Title items waiting in the inbox, the tickets passed to `update` never hold any.
+	pub titles: Option<Vec<wm::UserItem>>,
+	pub error: Option<String>,
+	pub pending: Option<task::Task<wm::UserItem>>,
+	pub pending_titles: Option<task::Task<Vec<wm::UserItem>>>,
 }
 
 fn show_tickets(car_items: &Vec<&wm::UserItem>, ui: &mut egui::Ui, new_item_buf: &mut Option<u32>) {
@@ -44,6 +49,15 @@ fn show_tickets(car_items: &Vec<&wm::UserItem>, ui: &mut egui::Ui, new_item_buf:
 		});
 }
 
+fn show_titles(titles: &[wm::UserItem], ui: &mut egui::Ui) {
+	if titles.is_empty() {
+		ui.label("No titles waiting to be received");
+	}
+	for item in titles {
+		ui.label(item.title_name());
+	}
+}
+
 fn wait_update_items(
 	car_items: &[wm::UserItem],
 	category: wm::ItemCategory,
@@ -52,7 +66,7 @@ fn wait_update_items(
 ) {
 	let mut car_items = car_items
 		.iter()
-		.filter(|i| i.category == category.into())
+		.filter(|i| i.category() == category)
 		.collect::<Vec<_>>();
 	car_items.sort_by(|a, b| a.item_id.cmp(&b.item_id));
 	if category == wm::ItemCategory::CatCarTicketFree {
@@ -63,18 +77,20 @@ fn wait_update_items(
 fn wait_select_category(ui: &mut egui::Ui) -> Option<wm::ItemCategory> {
 	if ui.button("Tickets").clicked() {
 		Some(wm::ItemCategory::CatCarTicketFree)
+	} else if ui.button("Titles").clicked() {
+		Some(wm::ItemCategory::CatCarTitle)
 	} else {
 		None
 	}
 }
 
 async fn update_user_items(
-	server: &Url,
+	client: &client::WmClient,
 	car: wm::Car,
 	setting: Option<wm::CarSetting>,
 	earned_user_items: Vec<wm::UserItem>,
 	odometer: u32,
-) -> Result<wm::UpdateCarResponse> {
+) -> Result<wm::SaveGameResultResponse> {
 	let req = wm::SaveGameResultRequest {
 		car_id: car.car_id(),
 		game_mode: wm::GameMode::ModeEvent as i32,
@@ -99,53 +115,179 @@ async fn update_user_items(
 		koshien_last_played_state: None,
 	};
 
-	wm::send_request(req, server, "method/save_game_result").await
+	client.save_game_result(req).await
+}
+
+async fn load_title_items(client: &client::WmClient, user_id: u32) -> Result<Vec<wm::UserItem>> {
+	let req = wm::LoadUnreceivedUserItemsRequest { user_id };
+	let mut items = client.load_unreceived_user_items(req).await?.owned_user_items;
+	items.retain(|item| item.category() == wm::ItemCategory::CatCarTitle);
+
+	Ok(items)
 }
 
 impl UserItems {
+	pub fn new(user_id: u32) -> Self {
+		Self {
+			user_id,
+			selected_category: None,
+			new_item_buf: None,
+			titles: None,
+			error: None,
+			pending: None,
+			pending_titles: None,
+		}
+	}
+
 	pub fn update(
 		&mut self,
 		ui: &mut egui::Ui,
 		runtime: &tokio::runtime::Runtime,
-		server: &Url,
+		client: &client::WmClient,
 		items: &mut Vec<wm::UserItem>,
 		car: &wm::Car,
 		odometer: u32,
 	) {
-		runtime.block_on(async {
+		match task::poll(&mut self.pending) {
+			Some(Ok(item)) => {
+				items.push(item);
+				self.new_item_buf = None;
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+		match task::poll(&mut self.pending_titles) {
+			Some(Ok(titles)) => {
+				self.titles = Some(titles);
+				self.error = None;
+			}
+			Some(Err(err)) => self.error = Some(err.to_string()),
+			None => {}
+		}
+
+		let mut new_item = None;
+		ui.add_enabled_ui(self.pending.is_none(), |ui| {
 			if self.selected_category.is_none() {
 				self.selected_category = wait_select_category(ui);
+			} else if self.selected_category == Some(wm::ItemCategory::CatCarTitle) {
+				if let Some(titles) = &self.titles {
+					show_titles(titles, ui);
+				}
 			} else if let Some(selected_category) = self.selected_category {
 				wait_update_items(items, selected_category, ui, &mut self.new_item_buf);
-				if ui.button("Add item").clicked()
+				// Titles come from events, so there's nothing to pick them from here
+				if selected_category == wm::ItemCategory::CatCarTicketFree
+					&& ui.button("Add item").clicked()
 					&& let Some(item_id) = self.new_item_buf
 				{
-					let item = wm::UserItem {
+					new_item = Some(wm::UserItem {
 						category: selected_category.into(),
 						item_id,
 						user_item_id: None,
 						earned_at: None,
 						expire_at: None,
 						title_name: None,
-					};
-					if update_user_items(server, car.clone(), None, vec![item.clone()], odometer)
-						.await
-						.is_ok()
-					{
-						items.push(item);
-						self.new_item_buf = None;
-					}
+					});
 				}
 			}
 		});
+
+		if let Some(item) = new_item {
+			let client = client.clone();
+			let car = car.clone();
+			self.pending = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				update_user_items(&client, car, None, vec![item.clone()], odometer).await?;
+				Ok(item)
+			}));
+		}
+		if self.selected_category == Some(wm::ItemCategory::CatCarTitle)
+			&& self.titles.is_none()
+			&& self.pending_titles.is_none()
+			&& self.error.is_none()
+		{
+			let client = client.clone();
+			let user_id = self.user_id;
+			self.pending_titles = Some(task::Task::spawn(runtime, ui.ctx(), async move {
+				load_title_items(&client, user_id).await
+			}));
+		}
+
+		if self.pending.is_some() || self.pending_titles.is_some() {
+			ui.spinner();
+		}
+		show_error(ui, &self.error);
 	}
 
 	pub fn back(&mut self) -> bool {
 		if self.selected_category.is_some() {
 			self.selected_category = None;
+			self.titles = None;
+			self.error = None;
 			false
 		} else {
 			true
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn update_user_items_grants_ticket() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		let car = server.state.lock().unwrap().car(mock::CAR_ID).unwrap().car.clone();
+		let ticket = wm::UserItem {
+			category: wm::ItemCategory::CatCarTicketFree.into(),
+			item_id: 5,
+			..Default::default()
+		};
+
+		update_user_items(&server.client(), car, None, vec![ticket], 1000)
+			.await
+			.unwrap();
+
+		let state = server.state.lock().unwrap();
+		let user = state.user(mock::USER_ID).unwrap();
+		assert_eq!(user.car_tickets.len(), 1);
+		assert_eq!(user.car_tickets[0].item_id, 5);
+		assert!(user.car_tickets[0].user_item_id.is_some());
+		assert!(user.unreceived_items.is_empty());
+	}
+
+	#[tokio::test]
+	async fn titles_come_from_unreceived_items() {
+		let server = mock::MockServer::start(mock::MockState::seeded()).await;
+		{
+			let mut state = server.state.lock().unwrap();
+			state.users[0].car_tickets = vec![wm::UserItem {
+				category: wm::ItemCategory::CatCarTicketFree.into(),
+				item_id: 5,
+				user_item_id: Some(1),
+				..Default::default()
+			}];
+			state.users[0].unreceived_items = vec![
+				wm::UserItem {
+					category: wm::ItemCategory::CatCarTitle.into(),
+					user_item_id: Some(2),
+					title_name: Some(String::from("Wangan King")),
+					..Default::default()
+				},
+				wm::UserItem {
+					category: wm::ItemCategory::CatBgm.into(),
+					item_id: 1,
+					user_item_id: Some(3),
+					..Default::default()
+				},
+			];
+		}
+
+		let titles = load_title_items(&server.client(), mock::USER_ID)
+			.await
+			.unwrap();
+		assert_eq!(titles.len(), 1);
+		assert_eq!(titles[0].title_name(), "Wangan King");
+	}
+}